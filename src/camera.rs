@@ -1,4 +1,4 @@
-use crate::{ray::Ray, vector3_traits::Helpers};
+use crate::{differential::RayDifferential, ray::Ray, vector3_traits::Helpers};
 
 use nalgebra::Vector3;
 
@@ -58,4 +58,19 @@ impl Camera {
             self.lower_left_corner + u * self.horizontal + v * self.vertical - self.origin;
         Ray::new(self.origin + offset, direction - offset)
     }
+
+    // Ray through (u, v) that also carries the rays through (u + du, v) and (u, v + dv), where du
+    // and dv are the size of a pixel in viewport coordinates.
+    pub fn get_ray_differential(&self, u: f64, v: f64, du: f64, dv: f64) -> Ray {
+        let mut ray = self.get_ray(u, v);
+
+        ray.differential = Some(RayDifferential {
+            origin_x: ray.origin,
+            direction_x: ray.direction + du * self.horizontal,
+            origin_y: ray.origin,
+            direction_y: ray.direction + dv * self.vertical,
+        });
+
+        ray
+    }
 }
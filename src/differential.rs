@@ -0,0 +1,145 @@
+use crate::hittable::HitRecord;
+use crate::texture::Footprint;
+
+use nalgebra::Vector3;
+
+// Offset rays through the neighbouring pixels in x and y of the image, which tell how large a
+// pixel is where the ray lands.
+#[derive(Copy, Clone)]
+pub struct RayDifferential {
+    pub origin_x: Vector3<f64>,
+    pub direction_x: Vector3<f64>,
+    pub origin_y: Vector3<f64>,
+    pub direction_y: Vector3<f64>,
+}
+
+impl RayDifferential {
+    // Points where the offset rays cross the tangent plane at the hit.
+    fn plane_points(&self, hit: &HitRecord) -> Option<(Vector3<f64>, Vector3<f64>)> {
+        let normal = hit.geometric_normal;
+        let distance = Vector3::dot(&normal, &hit.point);
+
+        let cross = |origin: &Vector3<f64>, direction: &Vector3<f64>| {
+            let t = (distance - Vector3::dot(&normal, origin)) / Vector3::dot(&normal, direction);
+
+            if t.is_finite() {
+                Some(origin + t * direction)
+            } else {
+                None
+            }
+        };
+
+        Some((
+            cross(&self.origin_x, &self.direction_x)?,
+            cross(&self.origin_y, &self.direction_y)?,
+        ))
+    }
+
+    // Change of the texture coordinates across the pixel, from the least squares solution of
+    // dp = du * dp/du + dv * dp/dv for the offsets to the neighbouring points.
+    pub fn footprint(&self, hit: &HitRecord) -> Footprint {
+        let (point_x, point_y) = match self.plane_points(hit) {
+            Some(points) => points,
+            None => return Footprint::default(),
+        };
+
+        let (dpdu, dpdv) = (hit.tangent, hit.bitangent);
+        let a = Vector3::dot(&dpdu, &dpdu);
+        let b = Vector3::dot(&dpdu, &dpdv);
+        let c = Vector3::dot(&dpdv, &dpdv);
+
+        let determinant = a * c - b * b;
+        if determinant <= 1e-12 * a * c || !determinant.is_finite() {
+            return Footprint::default();
+        }
+
+        let solve = |offset: Vector3<f64>| {
+            let (pu, pv) = (Vector3::dot(&dpdu, &offset), Vector3::dot(&dpdv, &offset));
+
+            (
+                (c * pu - b * pv) / determinant,
+                (a * pv - b * pu) / determinant,
+            )
+        };
+
+        let (dudx, dvdx) = solve(point_x - hit.point);
+        let (dudy, dvdy) = solve(point_y - hit.point);
+
+        Footprint {
+            dudx,
+            dvdx,
+            dudy,
+            dvdy,
+        }
+    }
+
+    // Differential after a perfectly specular bounce, treating the surface as locally flat. The
+    // offset rays leave from where they cross the tangent plane, with their directions mapped
+    // the same way as the main ray.
+    pub fn specular(
+        &self,
+        hit: &HitRecord,
+        map: impl Fn(Vector3<f64>) -> Vector3<f64>,
+    ) -> Option<RayDifferential> {
+        let (origin_x, origin_y) = self.plane_points(hit)?;
+
+        Some(RayDifferential {
+            origin_x,
+            direction_x: map(self.direction_x),
+            origin_y,
+            direction_y: map(self.direction_y),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::camera::Camera;
+    use crate::hittable::{HitRecord, Hittable};
+    use crate::material::LambertianMaterial;
+    use crate::shape::RectangleXY;
+    use crate::texture::SolidColorTexture;
+
+    use nalgebra::Vector3;
+
+    // Footprint at the middle of a 4x4 plane facing a camera with a 90 degree field of view.
+    fn footprint_at(distance: f64, pixel: f64) -> (f64, f64, f64, f64) {
+        let camera = Camera::new(
+            Vector3::zeros(),
+            Vector3::new(0.0, 0.0, -1.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            90.0,
+            1.0,
+            0.0,
+            1.0,
+        );
+        let white = LambertianMaterial::arc(SolidColorTexture::arc(Vector3::repeat(1.0)));
+        let plane = RectangleXY::arc((-2.0, 2.0), (-2.0, 2.0), -distance, white);
+
+        let ray = camera.get_ray_differential(0.5, 0.5, pixel, pixel);
+        let mut hit = HitRecord::new();
+        assert!(plane.hit(&ray, 0.0001, f64::MAX, &mut hit));
+
+        let footprint = ray.differential.unwrap().footprint(&hit);
+
+        (
+            footprint.dudx,
+            footprint.dvdx,
+            footprint.dudy,
+            footprint.dvdy,
+        )
+    }
+
+    #[test]
+    fn footprint_grows_with_the_distance_to_the_plane() {
+        // A pixel spans `2 pixel distance` world units, out of the 4 the texture covers.
+        for (distance, pixel) in [(1.0, 0.01), (4.0, 0.01), (4.0, 0.002)] {
+            let expected = 0.5 * pixel * distance;
+            let (dudx, dvdx, dudy, dvdy) = footprint_at(distance, pixel);
+
+            assert!((dudx - expected).abs() < 1e-12);
+            assert!((dvdy - expected).abs() < 1e-12);
+            assert!(dvdx.abs() < 1e-12 && dudy.abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,20 @@
+mod equirectangular;
+
+mod hdri;
+pub use hdri::HdriEnvironment;
+
+mod sky;
+pub use sky::SkyEnvironment;
+
+use nalgebra::Vector3;
+
+// Light arriving from infinitely far away, seen where rays leave the scene. It is also sampled
+// directly from diffuse surfaces, so it needs to pick directions and give their density.
+pub trait Environment: Sync + Send {
+    fn radiance(&self, direction: &Vector3<f64>) -> Vector3<f64>;
+
+    // Unit direction towards the environment and its probability density over solid angle.
+    fn sample(&self) -> (Vector3<f64>, f64);
+
+    fn pdf(&self, direction: &Vector3<f64>) -> f64;
+}
@@ -0,0 +1,208 @@
+use std::f64::consts::PI;
+
+use nalgebra::Vector3;
+use rand::Rng;
+
+// Distribution of directions proportional to a grid of luminances stored row by row, laid out
+// like an equirectangular image. It is piecewise constant over the image.
+pub struct EquirectangularDistribution {
+    width: usize,
+    height: usize,
+    weights: Vec<f64>,
+    total: f64,
+    // Cumulative weights of the rows, and within each row of its cells, normalized to end at one.
+    marginal: Vec<f64>,
+    conditional: Vec<Vec<f64>>,
+}
+
+impl EquirectangularDistribution {
+    pub fn new(luminance: &[f64], width: usize, height: usize) -> EquirectangularDistribution {
+        // Rows towards the poles cover less solid angle, so they are picked less often.
+        let mut weights: Vec<f64> = luminance
+            .iter()
+            .enumerate()
+            .map(|(i, luminance)| {
+                let sin_theta = (PI * ((i / width) as f64 + 0.5) / height as f64).sin();
+                luminance.max(0.0) * sin_theta
+            })
+            .collect();
+
+        // Without any weight the distribution falls back to uniform.
+        let mut total: f64 = weights.iter().sum();
+        if total <= 0.0 || !total.is_finite() {
+            weights = vec![1.0; width * height];
+            total = (width * height) as f64;
+        }
+
+        let conditional = weights
+            .chunks(width)
+            .map(EquirectangularDistribution::cumulative)
+            .collect();
+        let rows: Vec<f64> = weights.chunks(width).map(|row| row.iter().sum()).collect();
+        let marginal = EquirectangularDistribution::cumulative(&rows);
+
+        EquirectangularDistribution {
+            width,
+            height,
+            weights,
+            total,
+            marginal,
+            conditional,
+        }
+    }
+
+    fn cumulative(weights: &[f64]) -> Vec<f64> {
+        let total: f64 = weights.iter().sum();
+
+        let mut sum = 0.0;
+        let mut cumulative = vec![0.0];
+        for weight in weights {
+            sum += weight;
+            cumulative.push(if total > 0.0 { sum / total } else { 0.0 });
+        }
+
+        cumulative
+    }
+
+    // Index of the cell a uniform number falls into, skipping cells without weight.
+    fn find(cumulative: &[f64], value: f64) -> usize {
+        let index = cumulative.partition_point(|&c| c <= value);
+
+        index.clamp(1, cumulative.len() - 1) - 1
+    }
+
+    // Image coordinates from the top left corner, with the same longitude as the sphere UVs.
+    pub fn to_image(direction: &Vector3<f64>) -> (f64, f64) {
+        let theta = direction.y.clamp(-1.0, 1.0).acos();
+        let phi = f64::atan2(-direction.z, direction.x) + PI;
+
+        (phi / (2.0 * PI), theta / PI)
+    }
+
+    pub fn from_image((u, v): (f64, f64)) -> Vector3<f64> {
+        let theta = PI * v;
+        let phi = 2.0 * PI * u - PI;
+
+        Vector3::new(
+            theta.sin() * phi.cos(),
+            theta.cos(),
+            -theta.sin() * phi.sin(),
+        )
+    }
+
+    // Unit direction picked with probability proportional to the weights, and its density over
+    // solid angle.
+    pub fn sample(&self) -> (Vector3<f64>, f64) {
+        let mut rng = rand::thread_rng();
+
+        let row = EquirectangularDistribution::find(&self.marginal, rng.gen());
+        let column = EquirectangularDistribution::find(&self.conditional[row], rng.gen());
+
+        let direction = EquirectangularDistribution::from_image((
+            (column as f64 + rng.gen::<f64>()) / self.width as f64,
+            (row as f64 + rng.gen::<f64>()) / self.height as f64,
+        ));
+
+        (direction, self.pdf(&direction))
+    }
+
+    // Density over solid angle from the density over the image, which is stretched by 2π² sin θ.
+    pub fn pdf(&self, direction: &Vector3<f64>) -> f64 {
+        let sin_theta = (1.0 - direction.y * direction.y).max(0.0).sqrt();
+        if sin_theta <= 0.0 {
+            return 0.0;
+        }
+
+        let (u, v) = EquirectangularDistribution::to_image(direction);
+        let column = ((u * self.width as f64) as usize).min(self.width - 1);
+        let row = ((v * self.height as f64) as usize).min(self.height - 1);
+
+        let image_pdf = self.weights[row * self.width + column] * (self.width * self.height) as f64
+            / self.total;
+
+        image_pdf / (2.0 * PI * PI * sin_theta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A 8x4 image brighter towards its right and bottom.
+    fn gradient() -> EquirectangularDistribution {
+        let luminance: Vec<f64> = (0..32).map(|i| 1.0 + (i % 8 + i / 8) as f64).collect();
+        EquirectangularDistribution::new(&luminance, 8, 4)
+    }
+
+    #[test]
+    fn image_coordinates_round_trip() {
+        for direction in [
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.6, -0.8),
+            Vector3::new(-0.48, -0.6, 0.64),
+        ] {
+            let image = EquirectangularDistribution::to_image(&direction);
+            let back = EquirectangularDistribution::from_image(image);
+
+            assert!((back - direction).norm() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn pdf_integrates_to_one_over_the_sphere() {
+        let distribution = gradient();
+        let (rows, columns) = (400, 800);
+
+        let mut integral = 0.0;
+        for row in 0..rows {
+            let theta = PI * (row as f64 + 0.5) / rows as f64;
+            for column in 0..columns {
+                let phi = 2.0 * PI * (column as f64 + 0.5) / columns as f64;
+                let direction = Vector3::new(
+                    theta.sin() * phi.cos(),
+                    theta.cos(),
+                    theta.sin() * phi.sin(),
+                );
+
+                integral += distribution.pdf(&direction) * theta.sin();
+            }
+        }
+        integral *= (PI / rows as f64) * (2.0 * PI / columns as f64);
+
+        assert!((integral - 1.0).abs() < 1e-3, "{integral}");
+    }
+
+    #[test]
+    fn samples_match_their_pdf() {
+        let distribution = gradient();
+        let count = 20000;
+
+        // Each sample weighted by its inverse density estimates the area of the sphere.
+        let mut area = 0.0;
+        for _ in 0..count {
+            let (direction, pdf) = distribution.sample();
+
+            assert!((direction.norm() - 1.0).abs() < 1e-12);
+            assert!((pdf - distribution.pdf(&direction)).abs() < 1e-12);
+            area += 1.0 / pdf;
+        }
+        area /= count as f64;
+
+        assert!((area / (4.0 * PI) - 1.0).abs() < 0.05, "{area}");
+    }
+
+    #[test]
+    fn samples_skip_cells_without_weight() {
+        let mut luminance = vec![0.0; 32];
+        luminance[8 + 5] = 1.0;
+        let distribution = EquirectangularDistribution::new(&luminance, 8, 4);
+
+        for _ in 0..1000 {
+            let (direction, pdf) = distribution.sample();
+            let (u, v) = EquirectangularDistribution::to_image(&direction);
+
+            assert_eq!(((u * 8.0) as usize, (v * 4.0) as usize), (5, 1));
+            assert!(pdf > 0.0);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use super::equirectangular::EquirectangularDistribution;
+use super::Environment;
+
+use crate::texture::{ColorSpace, ImageTexture};
+use crate::vector3_traits::Helpers;
+
+use std::sync::Arc;
+
+use image::{ImageBuffer, Rgba};
+use nalgebra::{Rotation3, Vector3};
+
+// Equirectangular image around the scene, importance sampled by the luminance of its texels.
+pub struct HdriEnvironment {
+    data: ImageBuffer<Rgba<f32>, Vec<f32>>,
+    rotation: Rotation3<f64>,
+    intensity: f64,
+    distribution: EquirectangularDistribution,
+}
+
+impl HdriEnvironment {
+    // The rotation in degrees turns the image around the vertical axis.
+    pub fn arc(file_path: String, rotation: f64, intensity: f64) -> Arc<HdriEnvironment> {
+        let data = ImageTexture::load(&file_path, ColorSpace::of_file(&file_path));
+
+        let luminance: Vec<f64> = data
+            .pixels()
+            .map(|pixel| HdriEnvironment::color(pixel).luminance())
+            .collect();
+        let distribution = EquirectangularDistribution::new(
+            &luminance,
+            data.width() as usize,
+            data.height() as usize,
+        );
+
+        Arc::new(HdriEnvironment {
+            data,
+            rotation: Rotation3::from_axis_angle(&Vector3::y_axis(), rotation.to_radians()),
+            intensity,
+            distribution,
+        })
+    }
+
+    fn color(pixel: &Rgba<f32>) -> Vector3<f64> {
+        Vector3::new(pixel.0[0] as f64, pixel.0[1] as f64, pixel.0[2] as f64)
+    }
+}
+
+impl Environment for HdriEnvironment {
+    fn radiance(&self, direction: &Vector3<f64>) -> Vector3<f64> {
+        let (u, v) = EquirectangularDistribution::to_image(
+            &(self.rotation.inverse() * direction.normalize()),
+        );
+
+        let x = ((u * self.data.width() as f64) as u32).min(self.data.width() - 1);
+        let y = ((v * self.data.height() as f64) as u32).min(self.data.height() - 1);
+
+        HdriEnvironment::color(self.data.get_pixel(x, y)) * self.intensity
+    }
+
+    fn sample(&self) -> (Vector3<f64>, f64) {
+        let (direction, pdf) = self.distribution.sample();
+
+        (self.rotation * direction, pdf)
+    }
+
+    fn pdf(&self, direction: &Vector3<f64>) -> f64 {
+        self.distribution
+            .pdf(&(self.rotation.inverse() * direction.normalize()))
+    }
+}
@@ -0,0 +1,331 @@
+use super::equirectangular::EquirectangularDistribution;
+use super::Environment;
+
+use crate::spectrum;
+use crate::vector3_traits::Helpers;
+
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+use rand::Rng;
+
+// Brings luminances in kcd/m² to scene units, so that a clear zenith is around one.
+const LUMINANCE_SCALE: f64 = 0.1;
+
+// Luminance of the sun above the atmosphere, in kcd/m².
+const SUN_LUMINANCE: f64 = 2.0e6;
+const SUN_ANGULAR_RADIUS: f64 = 0.2725;
+
+// Wavelengths in micrometers at which the sun transmittance is computed for each channel.
+const CHANNEL_WAVELENGTHS: [f64; 3] = [0.65, 0.55, 0.45];
+
+// Resolution of the table the sky is sampled from.
+const TABLE_WIDTH: usize = 256;
+const TABLE_HEIGHT: usize = 128;
+
+// Analytic daylight after Preetham, Shirley and Smits (1999), with a sun disc and a uniformly lit
+// ground below the horizon.
+pub struct SkyEnvironment {
+    sun_direction: Vector3<f64>,
+    sun_radiance: Vector3<f64>,
+    cos_sun_radius: f64,
+    // Perez coefficients of the luminance and the two chromaticities, and their zenith values.
+    perez: [[f64; 5]; 3],
+    zenith: [f64; 3],
+    ground: Vector3<f64>,
+    // The sky is sampled from a table, and the sun with this probability from its cone.
+    distribution: EquirectangularDistribution,
+    sun_probability: f64,
+}
+
+impl SkyEnvironment {
+    // Angles are in degrees, with the azimuth turning from the x axis towards the z axis. The
+    // turbidity goes from about 2 for a clear sky to 10 for haze.
+    pub fn arc(
+        elevation: f64,
+        azimuth: f64,
+        turbidity: f64,
+        ground_albedo: Vector3<f64>,
+    ) -> Arc<SkyEnvironment> {
+        // The model only holds for a sun above the horizon and moderate turbidity.
+        let elevation = elevation.clamp(0.0, 90.0).to_radians();
+        let azimuth = azimuth.to_radians();
+        let turbidity = turbidity.clamp(1.7, 10.0);
+
+        let sun_direction = Vector3::new(
+            elevation.cos() * azimuth.cos(),
+            elevation.sin(),
+            elevation.cos() * azimuth.sin(),
+        );
+        let theta_sun = PI / 2.0 - elevation;
+
+        let t = turbidity;
+        let perez = [
+            [
+                0.1787 * t - 1.4630,
+                -0.3554 * t + 0.4275,
+                -0.0227 * t + 5.3251,
+                0.1206 * t - 2.5771,
+                -0.0670 * t + 0.3703,
+            ],
+            [
+                -0.0193 * t - 0.2592,
+                -0.0665 * t + 0.0008,
+                -0.0004 * t + 0.2125,
+                -0.0641 * t - 0.8989,
+                -0.0033 * t + 0.0452,
+            ],
+            [
+                -0.0167 * t - 0.2608,
+                -0.0950 * t + 0.0092,
+                -0.0079 * t + 0.2102,
+                -0.0441 * t - 1.6537,
+                -0.0109 * t + 0.0529,
+            ],
+        ];
+
+        let chi = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta_sun);
+        let zenith_luminance = (4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192;
+
+        let chromaticity = |coefficients: [[f64; 4]; 3]| {
+            let cubic = |c: [f64; 4]| {
+                c[0] * theta_sun.powi(3) + c[1] * theta_sun.powi(2) + c[2] * theta_sun + c[3]
+            };
+            t * t * cubic(coefficients[0]) + t * cubic(coefficients[1]) + cubic(coefficients[2])
+        };
+        let zenith_x = chromaticity([
+            [0.00166, -0.00375, 0.00209, 0.0],
+            [-0.02903, 0.06377, -0.03202, 0.00394],
+            [0.11693, -0.21196, 0.06052, 0.25886],
+        ]);
+        let zenith_y = chromaticity([
+            [0.00275, -0.00610, 0.00317, 0.0],
+            [-0.04214, 0.08970, -0.04153, 0.00516],
+            [0.15346, -0.26756, 0.06670, 0.26688],
+        ]);
+
+        // Sun dimmed by Rayleigh and aerosol extinction along the air mass it shines through.
+        let air_mass =
+            1.0 / (theta_sun.cos() + 0.15 * (93.885 - theta_sun.to_degrees()).powf(-1.253));
+        let beta = 0.04608 * t - 0.04586;
+        let transmittance = Vector3::from_iterator(CHANNEL_WAVELENGTHS.iter().map(|lambda| {
+            let rayleigh = 0.008735 * lambda.powf(-4.08);
+            let aerosol = beta * lambda.powf(-1.3);
+            (-(rayleigh + aerosol) * air_mass).exp()
+        }));
+        let sun_radiance = transmittance * SUN_LUMINANCE * LUMINANCE_SCALE;
+        let cos_sun_radius = SUN_ANGULAR_RADIUS.to_radians().cos();
+
+        let mut sky = SkyEnvironment {
+            sun_direction,
+            sun_radiance,
+            cos_sun_radius,
+            perez,
+            zenith: [zenith_luminance, zenith_x, zenith_y],
+            ground: Vector3::zeros(),
+            distribution: EquirectangularDistribution::new(&[1.0], 1, 1),
+            sun_probability: 0.0,
+        };
+
+        // Irradiance on the ground from the tabulated sky and the sun, reflected diffusely.
+        let directions: Vec<Vector3<f64>> = (0..TABLE_WIDTH * TABLE_HEIGHT)
+            .map(|i| {
+                EquirectangularDistribution::from_image((
+                    ((i % TABLE_WIDTH) as f64 + 0.5) / TABLE_WIDTH as f64,
+                    ((i / TABLE_WIDTH) as f64 + 0.5) / TABLE_HEIGHT as f64,
+                ))
+            })
+            .collect();
+        let texel_solid_angle = 2.0 * PI * PI / (TABLE_WIDTH * TABLE_HEIGHT) as f64;
+
+        let sun_solid_angle = 2.0 * PI * (1.0 - cos_sun_radius);
+        let sky_irradiance = directions
+            .iter()
+            .filter(|direction| direction.y > 0.0)
+            .map(|direction| {
+                let sin_theta = (1.0 - direction.y * direction.y).sqrt();
+                sky.sky_radiance(direction) * direction.y * sin_theta * texel_solid_angle
+            })
+            .sum::<Vector3<f64>>();
+        let irradiance = sky_irradiance + sun_radiance * sun_solid_angle * sun_direction.y;
+        sky.ground = ground_albedo.component_mul(&irradiance) / PI;
+
+        let luminance: Vec<f64> = directions
+            .iter()
+            .map(|direction| sky.sky_radiance(direction).luminance())
+            .collect();
+        sky.distribution = EquirectangularDistribution::new(&luminance, TABLE_WIDTH, TABLE_HEIGHT);
+
+        // Sun and sky are picked in proportion to their power, within bounds so neither starves.
+        let sky_power = luminance
+            .iter()
+            .zip(&directions)
+            .map(|(luminance, direction)| {
+                luminance * (1.0 - direction.y * direction.y).sqrt() * texel_solid_angle
+            })
+            .sum::<f64>();
+        let sun_power = sun_radiance.luminance() * sun_solid_angle;
+        sky.sun_probability = (sun_power / (sun_power + sky_power)).clamp(0.1, 0.9);
+
+        Arc::new(sky)
+    }
+
+    fn perez(coefficients: &[f64; 5], cos_theta: f64, gamma: f64) -> f64 {
+        let [a, b, c, d, e] = *coefficients;
+
+        (1.0 + a * (b / cos_theta).exp()) * (1.0 + c * (d * gamma).exp() + e * gamma.cos().powi(2))
+    }
+
+    // Radiance of the sky without the sun disc, or of the ground below the horizon.
+    fn sky_radiance(&self, direction: &Vector3<f64>) -> Vector3<f64> {
+        if direction.y <= 0.0 {
+            return self.ground;
+        }
+
+        let cos_theta = direction.y.max(1e-3);
+        let gamma = Vector3::dot(direction, &self.sun_direction)
+            .clamp(-1.0, 1.0)
+            .acos();
+        let theta_sun = self.sun_direction.y.clamp(-1.0, 1.0).acos();
+
+        let [luminance, x, y] = [0, 1, 2].map(|i| {
+            self.zenith[i] * SkyEnvironment::perez(&self.perez[i], cos_theta, gamma)
+                / SkyEnvironment::perez(&self.perez[i], 1.0, theta_sun)
+        });
+
+        let xyz = Vector3::new(x / y * luminance, luminance, (1.0 - x - y) / y * luminance);
+
+        spectrum::xyz_to_rgb(xyz) * LUMINANCE_SCALE
+    }
+
+    fn sun_pdf(&self, direction: &Vector3<f64>) -> f64 {
+        if Vector3::dot(direction, &self.sun_direction) < self.cos_sun_radius {
+            return 0.0;
+        }
+
+        1.0 / (2.0 * PI * (1.0 - self.cos_sun_radius))
+    }
+}
+
+impl Environment for SkyEnvironment {
+    fn radiance(&self, direction: &Vector3<f64>) -> Vector3<f64> {
+        let direction = direction.normalize();
+        let radiance = self.sky_radiance(&direction);
+
+        if direction.y > 0.0 && Vector3::dot(&direction, &self.sun_direction) >= self.cos_sun_radius
+        {
+            return radiance + self.sun_radiance;
+        }
+
+        radiance
+    }
+
+    fn sample(&self) -> (Vector3<f64>, f64) {
+        let mut rng = rand::thread_rng();
+
+        let direction = if rng.gen::<f64>() < self.sun_probability {
+            // Uniform over the cone of the sun disc.
+            let cos_theta = 1.0 - rng.gen::<f64>() * (1.0 - self.cos_sun_radius);
+            let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+            let phi = 2.0 * PI * rng.gen::<f64>();
+
+            let axis = if self.sun_direction.x.abs() > 0.9 {
+                Vector3::new(0.0, 1.0, 0.0)
+            } else {
+                Vector3::new(1.0, 0.0, 0.0)
+            };
+            let tangent = Vector3::cross(&axis, &self.sun_direction).normalize();
+            let bitangent = Vector3::cross(&self.sun_direction, &tangent);
+
+            (tangent * phi.cos() + bitangent * phi.sin()) * sin_theta
+                + self.sun_direction * cos_theta
+        } else {
+            self.distribution.sample().0
+        };
+
+        (direction, self.pdf(&direction))
+    }
+
+    fn pdf(&self, direction: &Vector3<f64>) -> f64 {
+        let direction = direction.normalize();
+
+        self.sun_probability * self.sun_pdf(&direction)
+            + (1.0 - self.sun_probability) * self.distribution.pdf(&direction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sky() -> Arc<SkyEnvironment> {
+        SkyEnvironment::arc(30.0, 60.0, 3.0, Vector3::repeat(0.3))
+    }
+
+    // Integral of the pdf over the angles from `from` to `to` radians away from the sun.
+    fn integral_around_sun(sky: &SkyEnvironment, from: f64, to: f64) -> f64 {
+        let (rows, columns) = (400, 800);
+
+        let sun = sky.sun_direction;
+        let tangent = Vector3::cross(&Vector3::new(1.0, 0.0, 0.0), &sun).normalize();
+        let bitangent = Vector3::cross(&sun, &tangent);
+
+        let d_theta = (to - from) / rows as f64;
+        let d_phi = 2.0 * PI / columns as f64;
+
+        let mut integral = 0.0;
+        for row in 0..rows {
+            let theta = from + (row as f64 + 0.5) * d_theta;
+            for column in 0..columns {
+                let phi = (column as f64 + 0.5) * d_phi;
+                let direction =
+                    (tangent * phi.cos() + bitangent * phi.sin()) * theta.sin() + sun * theta.cos();
+
+                integral += sky.pdf(&direction) * theta.sin() * d_theta * d_phi;
+            }
+        }
+
+        integral
+    }
+
+    #[test]
+    fn pdf_integrates_to_one_over_the_sphere() {
+        let sky = sky();
+
+        // The sun disc is far smaller than a step of the whole sphere, so it gets its own cap.
+        let cap = 2.0 * SUN_ANGULAR_RADIUS.to_radians();
+        let integral = integral_around_sun(&sky, 0.0, cap) + integral_around_sun(&sky, cap, PI);
+
+        assert!((integral - 1.0).abs() < 1e-3, "{integral}");
+    }
+
+    #[test]
+    fn samples_match_their_pdf() {
+        let sky = sky();
+        let count = 50000;
+
+        // Each sample weighted by its inverse density estimates the area of the sphere.
+        let (mut area, mut sun_samples) = (0.0, 0);
+        for _ in 0..count {
+            let (direction, pdf) = sky.sample();
+
+            assert!((direction.norm() - 1.0).abs() < 1e-9);
+            assert!((pdf - sky.pdf(&direction)).abs() <= 1e-9 * pdf);
+            area += 1.0 / pdf;
+
+            if sky.sun_pdf(&direction) > 0.0 {
+                sun_samples += 1;
+            }
+        }
+        area /= count as f64;
+
+        assert!((area / (4.0 * PI) - 1.0).abs() < 0.05, "{area}");
+
+        let sun_fraction = sun_samples as f64 / count as f64;
+        assert!(
+            (sun_fraction - sky.sun_probability).abs() < 0.02,
+            "{sun_fraction}"
+        );
+    }
+}
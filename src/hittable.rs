@@ -1,33 +1,120 @@
 use crate::aabb::AABB;
 use crate::material::*;
 use crate::ray::Ray;
-use crate::texture::SolidColorTexture;
+use crate::texture::{Footprint, SolidColorTexture, TexturePoint};
 
 use std::sync::Arc;
 
-use nalgebra::Vector3;
+use nalgebra::{Rotation3, Vector3};
 
+#[derive(Clone)]
 pub struct HitRecord {
     pub point: Vector3<f64>,
+    // Point on the shape before the transforms above it, which leave it untouched.
+    pub object_point: Vector3<f64>,
+    // Rotation taking directions from world space to the space of the object point.
+    pub object_rotation: Rotation3<f64>,
+    // Shading normal, which materials may perturb. Both normals face against the ray.
     pub normal: Vector3<f64>,
+    pub geometric_normal: Vector3<f64>,
+    // Derivatives of the point with respect to u and v. Their directions give the tangent frame
+    // and their lengths the size of the texture footprint.
+    pub tangent: Vector3<f64>,
+    pub bitangent: Vector3<f64>,
     pub t: f64,
     pub front_face: bool,
     pub material: Arc<dyn Material>,
     pub uv: (f64, f64),
+    pub footprint: Footprint,
 }
 
 impl HitRecord {
     pub fn new() -> HitRecord {
         HitRecord {
             point: Vector3::zeros(),
+            object_point: Vector3::zeros(),
+            object_rotation: Rotation3::identity(),
             normal: Vector3::zeros(),
+            geometric_normal: Vector3::zeros(),
+            tangent: Vector3::zeros(),
+            bitangent: Vector3::zeros(),
             t: f64::MAX,
             front_face: false,
             material: LambertianMaterial::arc(SolidColorTexture::arc(Vector3::new(1.0, 1.0, 1.0))),
             uv: (0.0, 0.0),
+            footprint: Footprint::default(),
         }
     }
 
+    pub fn texture_point(&self) -> TexturePoint {
+        TexturePoint::new(self.point, self.object_point)
+    }
+
+    // Texture point after moving the hit by a world space offset.
+    pub fn texture_point_offset(&self, offset: Vector3<f64>) -> TexturePoint {
+        TexturePoint::new(
+            self.point + offset,
+            self.object_point + self.object_rotation * offset,
+        )
+    }
+
+    // Orthonormal frame around the shading normal, with the tangent along the direction of
+    // increasing u. Falls back to an arbitrary tangent where the given one is degenerate.
+    pub fn tangent_frame(&self) -> (Vector3<f64>, Vector3<f64>) {
+        let mut tangent = self.tangent - self.normal * Vector3::dot(&self.tangent, &self.normal);
+        if tangent.magnitude_squared() < 1e-12 {
+            let axis = if self.normal.x.abs() > 0.9 {
+                Vector3::new(0.0, 1.0, 0.0)
+            } else {
+                Vector3::new(1.0, 0.0, 0.0)
+            };
+            tangent = Vector3::cross(&axis, &self.normal);
+        }
+        let tangent = tangent.normalize();
+
+        let mut bitangent = Vector3::cross(&self.normal, &tangent);
+        if Vector3::dot(&bitangent, &self.bitangent) < 0.0 {
+            bitangent = -bitangent;
+        }
+
+        (tangent, bitangent)
+    }
+
+    // Copy of the hit with the given shading normal. A normal turned away from the viewer cannot
+    // be shaded, so the geometry is used instead.
+    pub fn with_shading_normal(&self, normal: Vector3<f64>, ray: &Ray) -> HitRecord {
+        let mut shading = self.clone();
+
+        if Vector3::dot(&-ray.direction, &normal) > 0.0 {
+            shading.normal = normal;
+        }
+
+        shading
+    }
+
+    // Scatters with a material as if the surface had the given shading normal. Directions on
+    // opposite sides of the geometric and shading normals are dropped, so that perturbed normals
+    // cannot leak light through the surface.
+    pub fn scatter_with_shading_normal(
+        &self,
+        material: &Arc<dyn Material>,
+        normal: Vector3<f64>,
+        ray: &Ray,
+        attenuation: &mut Vector3<f64>,
+        scattered: &mut Ray,
+    ) -> bool {
+        let shading = self.with_shading_normal(normal, ray);
+
+        if !material.scatter(ray, &shading, attenuation, scattered) {
+            return false;
+        }
+
+        let geometric_side = Vector3::dot(&scattered.direction, &self.geometric_normal);
+        let shading_side = Vector3::dot(&scattered.direction, &shading.normal);
+
+        geometric_side * shading_side > 0.0
+    }
+
     pub fn set_face_normal(&mut self, direction: Vector3<f64>, normal: Vector3<f64>) {
         if Vector3::dot(&direction, &normal) > 0.0 {
             self.normal = -normal;
@@ -35,6 +122,8 @@ impl HitRecord {
         } else {
             self.front_face = true;
         }
+
+        self.geometric_normal = self.normal;
     }
 }
 
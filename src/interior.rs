@@ -0,0 +1,183 @@
+use crate::material::RefractiveIndex;
+
+use std::ptr;
+
+use nalgebra::Vector3;
+
+const CAPACITY: usize = 8;
+
+// Inside of a dielectric volume. Media live for the rest of the program once created, so that
+// rays can refer to them with plain references and stay cheap to copy.
+pub struct Medium {
+    pub priority: u32,
+    pub ir: RefractiveIndex,
+    pub absorption: Vector3<f64>,
+    pub scattering: Vector3<f64>,
+}
+
+impl Medium {
+    // The memory is never given back. Dielectric materials call this once each, the first time a
+    // ray enters them, so what is leaked is bounded by the materials of the scene rather than
+    // growing with the rays, and stays behind only if a material is dropped before the program ends.
+    pub fn leak(self) -> &'static Medium {
+        Box::leak(Box::new(self))
+    }
+
+    pub fn extinction(&self) -> Vector3<f64> {
+        self.absorption + self.scattering
+    }
+
+    pub fn is_scattering(&self) -> bool {
+        self.scattering.max() > 0.0
+    }
+}
+
+// Stack of the dielectric volumes a path is currently inside. It is stored by value on the ray
+// so that nested and overlapping volumes can resolve which medium lies on each side of a surface.
+// Media are told apart by their address.
+#[derive(Copy, Clone)]
+pub struct Interior {
+    media: [Option<&'static Medium>; CAPACITY],
+}
+
+impl Interior {
+    pub fn new() -> Interior {
+        Interior {
+            media: [None; CAPACITY],
+        }
+    }
+
+    pub fn enter(&mut self, medium: &'static Medium) {
+        if self
+            .media
+            .iter()
+            .flatten()
+            .any(|entered| ptr::eq(*entered, medium))
+        {
+            return;
+        }
+
+        if let Some(slot) = self.media.iter_mut().find(|slot| slot.is_none()) {
+            *slot = Some(medium);
+        }
+    }
+
+    pub fn exit(&mut self, medium: &Medium) {
+        if let Some(slot) = self
+            .media
+            .iter_mut()
+            .find(|slot| matches!(slot, Some(entered) if ptr::eq(*entered, medium)))
+        {
+            *slot = None;
+        }
+    }
+
+    // Medium the path is currently travelling through.
+    pub fn current(&self) -> Option<&'static Medium> {
+        self.dominant(None)
+    }
+
+    // Medium on the other side of the boundary of the given volume.
+    pub fn surrounding(&self, medium: &Medium) -> Option<&'static Medium> {
+        self.dominant(Some(medium))
+    }
+
+    fn dominant(&self, excluding: Option<&Medium>) -> Option<&'static Medium> {
+        self.media
+            .iter()
+            .flatten()
+            .filter(|medium| !excluding.is_some_and(|excluding| ptr::eq(**medium, excluding)))
+            .fold(None, |best: Option<&'static Medium>, medium| match best {
+                Some(best) if best.priority >= medium.priority => Some(best),
+                _ => Some(*medium),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn medium(priority: u32, ir: f64) -> &'static Medium {
+        Medium {
+            priority,
+            ir: RefractiveIndex::Constant(ir),
+            absorption: Vector3::zeros(),
+            scattering: Vector3::zeros(),
+        }
+        .leak()
+    }
+
+    fn is(found: Option<&Medium>, expected: &Medium) -> bool {
+        found.is_some_and(|found| ptr::eq(found, expected))
+    }
+
+    #[test]
+    fn starts_outside_every_medium() {
+        assert!(Interior::new().current().is_none());
+    }
+
+    #[test]
+    fn exit_leaves_the_entered_medium() {
+        let glass = medium(0, 1.5);
+        let mut interior = Interior::new();
+
+        interior.enter(glass);
+        assert!(is(interior.current(), glass));
+        assert!(interior.surrounding(glass).is_none());
+
+        interior.exit(glass);
+        assert!(interior.current().is_none());
+    }
+
+    #[test]
+    fn entering_twice_is_undone_by_one_exit() {
+        let glass = medium(0, 1.5);
+        let mut interior = Interior::new();
+
+        interior.enter(glass);
+        interior.enter(glass);
+        interior.exit(glass);
+
+        assert!(interior.current().is_none());
+    }
+
+    #[test]
+    fn media_with_equal_properties_are_distinct() {
+        let (first, second) = (medium(0, 1.5), medium(0, 1.5));
+        let mut interior = Interior::new();
+
+        interior.enter(first);
+        interior.enter(second);
+        interior.exit(first);
+
+        assert!(is(interior.current(), second));
+    }
+
+    #[test]
+    fn highest_priority_decides_the_medium() {
+        let (glass, water) = (medium(2, 1.5), medium(1, 1.33));
+        let mut interior = Interior::new();
+
+        // Water filling a glass overlaps its walls, which take precedence.
+        interior.enter(water);
+        interior.enter(glass);
+        assert!(is(interior.current(), glass));
+        assert!(is(interior.surrounding(glass), water));
+        assert!(is(interior.surrounding(water), glass));
+
+        interior.exit(glass);
+        assert!(is(interior.current(), water));
+    }
+
+    #[test]
+    fn exiting_an_unentered_medium_changes_nothing() {
+        let (glass, water) = (medium(0, 1.5), medium(0, 1.33));
+        let mut interior = Interior::new();
+
+        interior.enter(glass);
+        interior.exit(water);
+
+        assert!(is(interior.current(), glass));
+    }
+}
@@ -28,6 +28,14 @@ mod transform;
 
 mod obj_loader;
 
+mod interior;
+
+mod differential;
+
+mod spectrum;
+
+mod environment;
+
 use nalgebra::Vector3;
 use rand::Rng;
 use rayon::prelude::*;
@@ -41,7 +49,8 @@ pub const GAMMA: f64 = 1.0;
 
 fn main() {
     // World
-    let scene = Scene::monkey();
+    let name = std::env::args().nth(1).unwrap_or(String::from("monkey"));
+    let scene = Scene::named(&name).unwrap_or_else(|| panic!("Unknown scene {name}"));
 
     let counter = Arc::new(AtomicI64::from(0));
 
@@ -62,13 +71,19 @@ fn main() {
                         let v = (y as f64 + rand::thread_rng().gen::<f64>())
                             / (IMAGE_HEIGHT - 1) as f64;
 
-                        let ray = scene.camera.get_ray(u, v);
+                        let ray = scene.camera.get_ray_differential(
+                            u,
+                            v,
+                            1.0 / (IMAGE_WIDTH - 1) as f64,
+                            1.0 / (IMAGE_HEIGHT - 1) as f64,
+                        );
 
                         pixel_color = pixel_color
                             + Ray::ray_color(
                                 &ray,
                                 &scene.world,
                                 &scene.background_color,
+                                scene.environment.as_deref(),
                                 MAX_DEPTH,
                             );
                     }
@@ -94,6 +109,13 @@ fn main() {
     eprintln!("100.0 %");
     eprintln!();
     eprintln!("Done. Took {:.2} seconds.", instant.elapsed().as_secs_f64());
+
+    let (images, bytes) = scene.textures.memory_usage();
+    eprintln!(
+        "Textures: {} images, {:.2} MB.",
+        images,
+        bytes as f64 / (1024.0 * 1024.0)
+    );
 }
 
 fn save_image(file_path: &str, width: u32, height: u32, pixels: Vec<f64>) -> std::io::Result<()> {
@@ -1,15 +1,40 @@
+mod anisotropic_metal;
+mod bump_map;
+mod coated;
+mod cutout;
 mod dielectric;
 mod emissive;
 mod lambert;
 mod metal;
+mod mix;
+mod normal_map;
+mod oren_nayar;
+mod refractive_index;
+mod sheen;
+mod thin_dielectric;
+mod thin_film;
+mod translucent;
 
+pub use anisotropic_metal::AnisotropicMetalMaterial;
+pub use bump_map::BumpMapMaterial;
+pub use coated::CoatedMaterial;
+pub use cutout::CutoutMaterial;
 pub use dielectric::DielectricMaterial;
 pub use emissive::EmissiveMaterial;
 pub use lambert::LambertianMaterial;
 pub use metal::MetalMaterial;
+pub use mix::MixMaterial;
+pub use normal_map::NormalMapMaterial;
+pub use oren_nayar::OrenNayarMaterial;
+pub use refractive_index::RefractiveIndex;
+pub use sheen::SheenMaterial;
+pub use thin_dielectric::ThinDielectricMaterial;
+pub use thin_film::ThinFilm;
+pub use translucent::TranslucentMaterial;
 
 use crate::hittable::HitRecord;
 use crate::ray::Ray;
+use crate::texture::TexturePoint;
 
 pub use nalgebra::Vector3;
 pub use rand::Rng;
@@ -25,7 +50,31 @@ pub trait Material: Sync + Send {
         false
     }
 
-    fn emitted(&self, _uv: (f64, f64), _point: &Vector3<f64>) -> Vector3<f64> {
+    fn emitted(&self, _ray: &Ray, _hit: &HitRecord) -> Vector3<f64> {
         Vector3::zeros()
     }
+
+    // Reflectance times the cosine towards a unit direction, and the probability density with
+    // which `scatter` would pick it. Materials that can give it are also lit by sampling the
+    // lights directly, while the rest only see them through the directions they scatter to.
+    fn evaluate(
+        &self,
+        _ray: &Ray,
+        _hit: &HitRecord,
+        _direction: &Vector3<f64>,
+    ) -> Option<(Vector3<f64>, f64)> {
+        None
+    }
+
+    fn opacity(&self, _uv: (f64, f64), _point: &TexturePoint) -> f64 {
+        1.0
+    }
+
+    // Decides whether a ray stops at this point of the surface or goes through it. Fractional
+    // opacity is resolved stochastically.
+    fn alpha_test(&self, uv: (f64, f64), point: &TexturePoint) -> bool {
+        let opacity = self.opacity(uv, point);
+
+        opacity >= 1.0 || (opacity > 0.0 && opacity > rand::thread_rng().gen())
+    }
 }
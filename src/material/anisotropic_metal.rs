@@ -0,0 +1,140 @@
+use super::Material;
+
+use crate::hittable::HitRecord;
+use crate::ray::Ray;
+use crate::texture::Texture;
+
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+use rand::Rng;
+
+// Conductor with an anisotropic GGX microfacet distribution, for brushed metal. Roughness along
+// the tangent and bitangent of the surface can differ, and the rotation (in degrees) turns the
+// direction of the highlight around the normal.
+#[derive(Clone)]
+pub struct AnisotropicMetalMaterial {
+    albedo: Arc<dyn Texture>,
+    // Roughness along the tangent and the bitangent in [0, 1], read as scalar values.
+    roughness_u: Arc<dyn Texture>,
+    roughness_v: Arc<dyn Texture>,
+    sin_rotation: f64,
+    cos_rotation: f64,
+}
+
+impl AnisotropicMetalMaterial {
+    pub fn arc(
+        albedo: Arc<dyn Texture>,
+        roughness_u: Arc<dyn Texture>,
+        roughness_v: Arc<dyn Texture>,
+        rotation: f64,
+    ) -> Arc<AnisotropicMetalMaterial> {
+        let radians = rotation.to_radians();
+
+        Arc::new(AnisotropicMetalMaterial {
+            albedo,
+            roughness_u,
+            roughness_v,
+            sin_rotation: radians.sin(),
+            cos_rotation: radians.cos(),
+        })
+    }
+
+    fn alpha(roughness: f64) -> f64 {
+        (roughness * roughness).max(1e-4)
+    }
+
+    // Samples a microfacet normal from the distribution of normals visible from `view`, given in
+    // the local frame where z is the surface normal (Heitz, 2018).
+    fn sample_visible_normal(alpha: (f64, f64), view: Vector3<f64>) -> Vector3<f64> {
+        let mut rng = rand::thread_rng();
+        let (alpha_u, alpha_v) = alpha;
+
+        let stretched = Vector3::new(alpha_u * view.x, alpha_v * view.y, view.z).normalize();
+
+        let length_squared = stretched.x * stretched.x + stretched.y * stretched.y;
+        let t1 = if length_squared > 0.0 {
+            Vector3::new(-stretched.y, stretched.x, 0.0) / length_squared.sqrt()
+        } else {
+            Vector3::new(1.0, 0.0, 0.0)
+        };
+        let t2 = Vector3::cross(&stretched, &t1);
+
+        let radius = rng.gen::<f64>().sqrt();
+        let phi = 2.0 * PI * rng.gen::<f64>();
+        let p1 = radius * phi.cos();
+        let s = 0.5 * (1.0 + stretched.z);
+        let p2 = (1.0 - s) * (1.0 - p1 * p1).sqrt() + s * radius * phi.sin();
+
+        let normal = p1 * t1 + p2 * t2 + (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt() * stretched;
+
+        Vector3::new(alpha_u * normal.x, alpha_v * normal.y, normal.z.max(0.0)).normalize()
+    }
+
+    fn lambda(alpha: (f64, f64), direction: Vector3<f64>) -> f64 {
+        let (alpha_u, alpha_v) = alpha;
+        let projected = (alpha_u * direction.x).powi(2) + (alpha_v * direction.y).powi(2);
+        let tan2 = projected / (direction.z * direction.z);
+
+        0.5 * (-1.0 + (1.0 + tan2).sqrt())
+    }
+}
+
+impl Material for AnisotropicMetalMaterial {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        attenuation: &mut Vector3<f64>,
+        scattered: &mut Ray,
+    ) -> bool {
+        let (tangent, bitangent) = hit.tangent_frame();
+        let tangent_rotated = self.cos_rotation * tangent + self.sin_rotation * bitangent;
+        let bitangent_rotated = -self.sin_rotation * tangent + self.cos_rotation * bitangent;
+
+        let to_local = |direction: Vector3<f64>| {
+            Vector3::new(
+                Vector3::dot(&direction, &tangent_rotated),
+                Vector3::dot(&direction, &bitangent_rotated),
+                Vector3::dot(&direction, &hit.normal),
+            )
+        };
+
+        let view = to_local(-ray.direction.normalize());
+        if view.z <= 0.0 {
+            return false;
+        }
+
+        let point = hit.texture_point();
+        let alpha = (
+            AnisotropicMetalMaterial::alpha(self.roughness_u.get_value(hit.uv, &point)),
+            AnisotropicMetalMaterial::alpha(self.roughness_v.get_value(hit.uv, &point)),
+        );
+
+        let microfacet = AnisotropicMetalMaterial::sample_visible_normal(alpha, view);
+        let light = 2.0 * Vector3::dot(&view, &microfacet) * microfacet - view;
+        if light.z <= 0.0 {
+            return false;
+        }
+
+        // Weight of visible normal sampling: Fresnel times the masking-shadowing ratio G2 / G1.
+        let lambda_view = AnisotropicMetalMaterial::lambda(alpha, view);
+        let masking = (1.0 + lambda_view)
+            / (1.0 + lambda_view + AnisotropicMetalMaterial::lambda(alpha, light));
+
+        let f0 = self
+            .albedo
+            .get_color_filtered(hit.uv, &point, &hit.footprint);
+        let schlick = (1.0 - Vector3::dot(&view, &microfacet).clamp(0.0, 1.0)).powi(5);
+        let fresnel = f0 + (Vector3::new(1.0, 1.0, 1.0) - f0) * schlick;
+
+        let direction =
+            light.x * tangent_rotated + light.y * bitangent_rotated + light.z * hit.normal;
+
+        *scattered = ray.child(hit.point, direction);
+        *attenuation = fresnel * masking;
+
+        true
+    }
+}
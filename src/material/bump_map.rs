@@ -0,0 +1,161 @@
+use super::Material;
+
+use crate::hittable::HitRecord;
+use crate::ray::Ray;
+use crate::texture::Texture;
+use crate::texture::TexturePoint;
+
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+
+// Offset in texture coordinates for the finite differences of the height. Points for solid
+// textures move along the surface by as much as the texture coordinates do.
+const DELTA: f64 = 0.001;
+
+// Perturbs the shading normal of a material as if the surface was displaced by a height texture,
+// read as a scalar value and scaled by the strength.
+#[derive(Clone)]
+pub struct BumpMapMaterial {
+    material: Arc<dyn Material>,
+    height: Arc<dyn Texture>,
+    strength: f64,
+}
+
+impl BumpMapMaterial {
+    // Heights are data rather than colors, so an image for them has to be decoded with
+    // `ColorSpace::Linear`, as `TextureCache::arc_linear` does.
+    pub fn arc(
+        material: Arc<dyn Material>,
+        height: Arc<dyn Texture>,
+        strength: f64,
+    ) -> Arc<BumpMapMaterial> {
+        Arc::new(BumpMapMaterial {
+            material,
+            height,
+            strength,
+        })
+    }
+
+    fn shading_normal(&self, hit: &HitRecord) -> Vector3<f64> {
+        let (tangent, bitangent) = hit.tangent_frame();
+        let (u, v) = hit.uv;
+
+        // Differences are taken towards the inside of the [0, 1] texture range.
+        let step_u = if u + DELTA <= 1.0 { DELTA } else { -DELTA };
+        let step_v = if v + DELTA <= 1.0 { DELTA } else { -DELTA };
+
+        // Surface distance per unit of texture coordinates, so that slopes are taken per world
+        // unit and do not depend on how the texture is stretched over the surface.
+        let length = |derivative: Vector3<f64>| match derivative.norm() {
+            norm if norm > 0.0 => norm,
+            _ => 1.0,
+        };
+        let (distance_u, distance_v) =
+            (step_u * length(hit.tangent), step_v * length(hit.bitangent));
+
+        let height = self.height.get_value(hit.uv, &hit.texture_point());
+        let height_u = self.height.get_value(
+            (u + step_u, v),
+            &hit.texture_point_offset(distance_u * tangent),
+        );
+        let height_v = self.height.get_value(
+            (u, v + step_v),
+            &hit.texture_point_offset(distance_v * bitangent),
+        );
+
+        let slope_u = self.strength * (height_u - height) / distance_u;
+        let slope_v = self.strength * (height_v - height) / distance_v;
+
+        let outward = if hit.front_face {
+            hit.normal
+        } else {
+            -hit.normal
+        };
+
+        let normal = (outward - slope_u * tangent - slope_v * bitangent).normalize();
+        if hit.front_face {
+            normal
+        } else {
+            -normal
+        }
+    }
+}
+
+impl Material for BumpMapMaterial {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        attenuation: &mut Vector3<f64>,
+        scattered: &mut Ray,
+    ) -> bool {
+        hit.scatter_with_shading_normal(
+            &self.material,
+            self.shading_normal(hit),
+            ray,
+            attenuation,
+            scattered,
+        )
+    }
+
+    fn evaluate(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        direction: &Vector3<f64>,
+    ) -> Option<(Vector3<f64>, f64)> {
+        let shading = hit.with_shading_normal(self.shading_normal(hit), ray);
+
+        self.material.evaluate(ray, &shading, direction)
+    }
+
+    fn emitted(&self, ray: &Ray, hit: &HitRecord) -> Vector3<f64> {
+        self.material.emitted(ray, hit)
+    }
+
+    fn opacity(&self, uv: (f64, f64), point: &TexturePoint) -> f64 {
+        self.material.opacity(uv, point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::hittable::Hittable;
+    use crate::material::LambertianMaterial;
+    use crate::shape::RectangleXY;
+    use crate::texture::SolidColorTexture;
+
+    // Rises by one unit across the texture along u.
+    struct Ramp;
+
+    impl Texture for Ramp {
+        fn get_color(&self, uv: (f64, f64), _point: &TexturePoint) -> Vector3<f64> {
+            Vector3::repeat(uv.0)
+        }
+    }
+
+    fn shading_normal(width: f64) -> Vector3<f64> {
+        let white = SolidColorTexture::arc(Vector3::repeat(1.0));
+        let bump = BumpMapMaterial::arc(LambertianMaterial::arc(white), Arc::new(Ramp), 1.0);
+        let quad = RectangleXY::arc((-width, width), (-1.0, 1.0), 0.0, bump.clone());
+
+        let ray = Ray::new(Vector3::new(0.2, 0.1, 5.0), Vector3::new(0.0, 0.0, -1.0));
+        let mut hit = HitRecord::new();
+        assert!(quad.hit(&ray, 0.0001, f64::MAX, &mut hit));
+
+        bump.shading_normal(&hit)
+    }
+
+    #[test]
+    fn slopes_are_taken_per_world_unit() {
+        // The texture spans twice the width, so the height rises by 1 / (2 width) per unit.
+        for width in [1.0, 4.0] {
+            let expected = Vector3::new(-1.0 / (2.0 * width), 0.0, 1.0).normalize();
+
+            assert!((shading_normal(width) - expected).norm() < 1e-6);
+        }
+    }
+}
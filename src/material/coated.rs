@@ -0,0 +1,137 @@
+use super::DielectricMaterial;
+use super::Material;
+
+use crate::hittable::HitRecord;
+use crate::ray::Ray;
+use crate::texture::TexturePoint;
+use crate::vector3_traits::Helpers;
+
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+use rand::Rng;
+
+const MAX_INTERNAL_BOUNCES: u32 = 16;
+
+// Smooth dielectric coat over any base material, such as varnish or car paint clear coat.
+// Light bouncing between the coat and the base is followed stochastically, so no energy is added.
+// The coat reflects like a mirror, so the material is not evaluated in given directions and only
+// sees lights through the directions it scatters to, even over a base that could be evaluated.
+#[derive(Clone)]
+pub struct CoatedMaterial {
+    base: Arc<dyn Material>,
+    ir: f64,
+    thickness: f64,
+    // Transmittance of the coat per unit distance.
+    tint: Vector3<f64>,
+}
+
+impl CoatedMaterial {
+    pub fn arc(
+        base: Arc<dyn Material>,
+        ir: f64,
+        thickness: f64,
+        tint: Vector3<f64>,
+    ) -> Arc<CoatedMaterial> {
+        Arc::new(CoatedMaterial {
+            base,
+            ir,
+            thickness,
+            tint,
+        })
+    }
+
+    fn transmittance(&self, cosine: f64) -> Vector3<f64> {
+        let distance = self.thickness / cosine.max(0.0001);
+        self.tint.map(|t| t.powf(distance))
+    }
+}
+
+impl Material for CoatedMaterial {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        attenuation: &mut Vector3<f64>,
+        scattered: &mut Ray,
+    ) -> bool {
+        // The coat only covers the outside of the surface.
+        if !hit.front_face {
+            return self.base.scatter(ray, hit, attenuation, scattered);
+        }
+
+        let mut rng = rand::thread_rng();
+
+        let direction_normalized = ray.direction.normalize();
+        let cos_theta = f64::min(Vector3::dot(&-direction_normalized, &hit.normal), 1.0);
+
+        if DielectricMaterial::reflectance(cos_theta, 1.0 / self.ir) > rng.gen() {
+            *attenuation = Vector3::new(1.0, 1.0, 1.0);
+            *scattered = ray.child(
+                hit.point,
+                Vector3::reflection(direction_normalized, hit.normal),
+            );
+
+            return true;
+        }
+
+        let refracted = Vector3::refraction(direction_normalized, hit.normal, 1.0 / self.ir);
+        let mut throughput = self.transmittance(-Vector3::dot(&refracted, &hit.normal));
+        let mut inner = ray.child(hit.point, refracted);
+
+        for _ in 0..MAX_INTERNAL_BOUNCES {
+            let mut base_attenuation = Vector3::zeros();
+            let mut base_scattered = Ray::zeros();
+
+            if !self
+                .base
+                .scatter(&inner, hit, &mut base_attenuation, &mut base_scattered)
+            {
+                return false;
+            }
+
+            throughput = throughput.component_mul(&base_attenuation);
+
+            let direction = base_scattered.direction.normalize();
+            let cos_out = Vector3::dot(&direction, &hit.normal);
+
+            // The base sent the path below the surface, so the coat is no longer involved.
+            if cos_out <= 0.0 {
+                *attenuation = throughput;
+                *scattered = base_scattered;
+
+                return true;
+            }
+
+            throughput = throughput.component_mul(&self.transmittance(cos_out));
+
+            let sin_out = (1.0 - cos_out * cos_out).sqrt();
+            if self.ir * sin_out > 1.0
+                || DielectricMaterial::reflectance(cos_out, self.ir) > rng.gen()
+            {
+                // Reflected back down at the top of the coat, so it reaches the base again.
+                throughput = throughput.component_mul(&self.transmittance(cos_out));
+                inner = base_scattered.child(hit.point, Vector3::reflection(direction, hit.normal));
+                continue;
+            }
+
+            *attenuation = throughput;
+            *scattered = base_scattered.child(
+                hit.point,
+                Vector3::refraction(direction, -hit.normal, self.ir),
+            );
+
+            return true;
+        }
+
+        false
+    }
+
+    fn emitted(&self, ray: &Ray, hit: &HitRecord) -> Vector3<f64> {
+        self.base.emitted(ray, hit)
+    }
+
+    fn opacity(&self, uv: (f64, f64), point: &TexturePoint) -> f64 {
+        self.base.opacity(uv, point)
+    }
+}
@@ -0,0 +1,119 @@
+use super::Material;
+
+use crate::hittable::HitRecord;
+use crate::ray::Ray;
+use crate::texture::Texture;
+use crate::texture::TexturePoint;
+
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+
+// Masks a material with the alpha channel of a texture, for foliage cards or fences modeled as
+// quads. Shapes skip the transparent parts of the surface, so rays continue behind them.
+#[derive(Clone)]
+pub struct CutoutMaterial {
+    material: Arc<dyn Material>,
+    opacity: Arc<dyn Texture>,
+}
+
+impl CutoutMaterial {
+    pub fn arc(material: Arc<dyn Material>, opacity: Arc<dyn Texture>) -> Arc<CutoutMaterial> {
+        Arc::new(CutoutMaterial { material, opacity })
+    }
+}
+
+impl Material for CutoutMaterial {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        attenuation: &mut Vector3<f64>,
+        scattered: &mut Ray,
+    ) -> bool {
+        self.material.scatter(ray, hit, attenuation, scattered)
+    }
+
+    fn emitted(&self, ray: &Ray, hit: &HitRecord) -> Vector3<f64> {
+        self.material.emitted(ray, hit)
+    }
+
+    fn evaluate(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        direction: &Vector3<f64>,
+    ) -> Option<(Vector3<f64>, f64)> {
+        self.material.evaluate(ray, hit, direction)
+    }
+
+    fn opacity(&self, uv: (f64, f64), point: &TexturePoint) -> f64 {
+        self.opacity.get_alpha(uv, point) * self.material.opacity(uv, point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::hittable::Hittable;
+    use crate::material::{CoatedMaterial, LambertianMaterial, SheenMaterial};
+    use crate::shape::RectangleXY;
+    use crate::texture::SolidColorTexture;
+    use crate::transform::Translate;
+
+    // Opaque where the world space x is positive.
+    struct RightHalf;
+
+    impl Texture for RightHalf {
+        fn get_color(&self, _uv: (f64, f64), _point: &TexturePoint) -> Vector3<f64> {
+            Vector3::repeat(1.0)
+        }
+
+        fn get_alpha(&self, _uv: (f64, f64), point: &TexturePoint) -> f64 {
+            if point.world.x > 0.0 {
+                1.0
+            } else {
+                0.0
+            }
+        }
+    }
+
+    fn cutout() -> Arc<CutoutMaterial> {
+        let white = SolidColorTexture::arc(Vector3::repeat(1.0));
+        CutoutMaterial::arc(LambertianMaterial::arc(white), Arc::new(RightHalf))
+    }
+
+    fn hits(hittable: &dyn Hittable, x: f64) -> bool {
+        let ray = Ray::new(Vector3::new(x, 0.0, 5.0), Vector3::new(0.0, 0.0, -1.0));
+        hittable.hit(&ray, 0.0001, f64::MAX, &mut HitRecord::new())
+    }
+
+    #[test]
+    fn transformed_shapes_test_alpha_at_the_world_point() {
+        // Spans x in [-1, 1] in world space, and [-3, -1] before the translation.
+        let quad = Translate::arc(
+            Vector3::new(2.0, 0.0, 0.0),
+            RectangleXY::arc((-3.0, -1.0), (-1.0, 1.0), 0.0, cutout()),
+        );
+
+        assert!(hits(quad.as_ref(), 0.5));
+        assert!(!hits(quad.as_ref(), -0.5));
+    }
+
+    #[test]
+    fn layers_keep_the_cutout_of_their_base() {
+        let white = SolidColorTexture::arc(Vector3::repeat(1.0));
+        let layers: [Arc<dyn Material>; 2] = [
+            CoatedMaterial::arc(cutout(), 1.5, 0.0, Vector3::repeat(1.0)),
+            SheenMaterial::arc_layered(white.clone(), white, cutout()),
+        ];
+
+        for layer in layers {
+            let quad = RectangleXY::arc((-1.0, 1.0), (-1.0, 1.0), 0.0, layer);
+
+            assert!(hits(quad.as_ref(), 0.5));
+            assert!(!hits(quad.as_ref(), -0.5));
+        }
+    }
+}
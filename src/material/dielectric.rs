@@ -1,25 +1,101 @@
 use super::Material;
+use super::RefractiveIndex;
+use super::ThinFilm;
 
 use crate::hittable::HitRecord;
+use crate::interior::Medium;
 use crate::ray::Ray;
+use crate::spectrum;
 use crate::vector3_traits::Helpers;
 
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use nalgebra::Vector3;
 use rand::Rng;
 
-#[derive(Clone)]
 pub struct DielectricMaterial {
-    ir: f64,
+    ir: RefractiveIndex,
+    absorption: Vector3<f64>,
+    scattering: Vector3<f64>,
+    priority: u32,
+    thin_film: Option<ThinFilm>,
+    // Created on the first scatter, once the material can no longer be configured.
+    medium: OnceLock<&'static Medium>,
 }
 
 impl DielectricMaterial {
+    pub fn new(ir: RefractiveIndex) -> DielectricMaterial {
+        DielectricMaterial {
+            ir,
+            absorption: Vector3::zeros(),
+            scattering: Vector3::zeros(),
+            priority: 0,
+            thin_film: None,
+            medium: OnceLock::new(),
+        }
+    }
+
     pub fn arc(ir: f64) -> Arc<DielectricMaterial> {
-        Arc::new(DielectricMaterial { ir: ir })
+        Arc::new(DielectricMaterial::new(RefractiveIndex::Constant(ir)))
+    }
+
+    pub fn arc_dispersive(ir: RefractiveIndex) -> Arc<DielectricMaterial> {
+        Arc::new(DielectricMaterial::new(ir))
+    }
+
+    // Dielectric boundary around a scattering interior, for skin, wax, marble or milk.
+    pub fn arc_subsurface(
+        ir: f64,
+        mean_free_path: Vector3<f64>,
+        albedo: Vector3<f64>,
+    ) -> Arc<DielectricMaterial> {
+        let mut material = DielectricMaterial::new(RefractiveIndex::Constant(ir));
+        material.set_subsurface(mean_free_path, albedo);
+
+        Arc::new(material)
+    }
+
+    // Absorption coefficient per unit distance travelled inside the material.
+    pub fn set_absorption(&mut self, absorption: Vector3<f64>) {
+        self.absorption = absorption;
+    }
+
+    // Color that white light takes after travelling `distance` inside the material.
+    pub fn set_color_at_distance(&mut self, color: Vector3<f64>, distance: f64) {
+        self.absorption = color.map(|c| -c.max(f64::MIN_POSITIVE).ln() / distance);
+    }
+
+    // Per channel mean distance between interactions inside the material, and the fraction of
+    // each interaction that scatters rather than absorbs.
+    pub fn set_subsurface(&mut self, mean_free_path: Vector3<f64>, albedo: Vector3<f64>) {
+        let extinction = mean_free_path.map(|distance| 1.0 / distance);
+
+        self.scattering = extinction.component_mul(&albedo);
+        self.absorption = extinction - self.scattering;
+    }
+
+    // Where volumes overlap, the one with the highest priority decides the medium.
+    pub fn set_priority(&mut self, priority: u32) {
+        self.priority = priority;
+    }
+
+    pub fn set_thin_film(&mut self, thin_film: ThinFilm) {
+        self.thin_film = Some(thin_film);
+    }
+
+    fn medium(&self) -> &'static Medium {
+        self.medium.get_or_init(|| {
+            Medium {
+                priority: self.priority,
+                ir: self.ir,
+                absorption: self.absorption,
+                scattering: self.scattering,
+            }
+            .leak()
+        })
     }
 
-    fn reflectance(cosine: f64, ref_index: f64) -> f64 {
+    pub fn reflectance(cosine: f64, ref_index: f64) -> f64 {
         let r0 = (1.0 - ref_index) / (1.0 + ref_index);
         let r0 = r0 * r0;
 
@@ -37,10 +113,48 @@ impl Material for DielectricMaterial {
     ) -> bool {
         *attenuation = Vector3::new(1.0, 1.0, 1.0);
 
+        // A dispersive material commits the path to a single wavelength the first time it is hit.
+        let mut wavelength = ray.wavelength;
+        if self.ir.is_dispersive() && wavelength.is_none() {
+            let sampled = spectrum::sample_wavelength();
+            *attenuation = spectrum::wavelength_to_rgb(sampled);
+            wavelength = Some(sampled);
+        }
+
+        let wavelength_or_d = wavelength.unwrap_or(spectrum::WAVELENGTH_D);
+        let ir = self.ir.at(wavelength_or_d);
+
+        let medium = self.medium();
+        let mut interior = ray.interior;
+        let surrounding = interior.surrounding(medium);
+
+        // Inside a volume with a higher priority this boundary does not exist, so the path carries
+        // on unchanged and only the interior stack is updated.
+        if let Some(surrounding) = surrounding {
+            if surrounding.priority > self.priority {
+                if hit.front_face {
+                    interior.enter(medium);
+                } else {
+                    interior.exit(medium);
+                }
+
+                *scattered = ray.child(hit.point, ray.direction);
+                scattered.wavelength = wavelength;
+                scattered.interior = interior;
+                scattered.differential = ray
+                    .differential
+                    .and_then(|differential| differential.specular(hit, |direction| direction));
+
+                return true;
+            }
+        }
+
+        let outside_ir = surrounding.map_or(1.0, |surrounding| surrounding.ir.at(wavelength_or_d));
+
         let refraction_ratio = if hit.front_face {
-            1.0 / self.ir
+            outside_ir / ir
         } else {
-            self.ir
+            ir / outside_ir
         };
 
         let direction_normalized = ray.direction.normalize();
@@ -50,18 +164,76 @@ impl Material for DielectricMaterial {
 
         let cannot_refract: bool = refraction_ratio * sin_theta > 1.0;
 
-        let direction: Vector3<f64>;
-        if cannot_refract == true
-            || DielectricMaterial::reflectance(cos_theta, refraction_ratio)
-                > rand::thread_rng().gen()
-        {
-            direction = Vector3::reflection(direction_normalized, hit.normal);
+        let reflectance = match &self.thin_film {
+            Some(thin_film) => {
+                let (incident_ir, substrate_ir) = if hit.front_face {
+                    (outside_ir, ir)
+                } else {
+                    (ir, outside_ir)
+                };
+
+                thin_film.reflectance(
+                    cos_theta,
+                    incident_ir,
+                    substrate_ir,
+                    wavelength,
+                    hit.uv,
+                    &hit.texture_point(),
+                )
+            }
+            None => Vector3::repeat(DielectricMaterial::reflectance(cos_theta, refraction_ratio)),
+        };
+        let reflect_probability = reflectance.mean();
+
+        let reflected: bool;
+        if cannot_refract == true {
+            reflected = true;
+        } else if reflect_probability > rand::thread_rng().gen() {
+            *attenuation = attenuation.component_mul(&reflectance) / reflect_probability;
+            reflected = true;
         } else {
-            direction = Vector3::refraction(direction_normalized, hit.normal, refraction_ratio);
+            *attenuation = attenuation.component_mul(&(Vector3::repeat(1.0) - reflectance))
+                / (1.0 - reflect_probability);
+            reflected = false;
+
+            if hit.front_face {
+                interior.enter(medium);
+            } else {
+                interior.exit(medium);
+            }
         }
 
-        *scattered = Ray::new(hit.point, direction);
+        // Shared with the offset rays of the differential, which bend the same way.
+        let bend = |direction: Vector3<f64>| {
+            if reflected {
+                Vector3::reflection(direction.normalize(), hit.normal)
+            } else {
+                Vector3::refraction(direction.normalize(), hit.normal, refraction_ratio)
+            }
+        };
+
+        *scattered = ray.child(hit.point, bend(direction_normalized));
+        scattered.wavelength = wavelength;
+        scattered.interior = interior;
+        scattered.differential = ray
+            .differential
+            .and_then(|differential| differential.specular(hit, bend));
 
         return true;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::ptr;
+
+    #[test]
+    fn each_material_leaks_a_single_medium() {
+        let glass = DielectricMaterial::new(RefractiveIndex::Constant(1.5));
+
+        assert!(ptr::eq(glass.medium(), glass.medium()));
+        assert_eq!(glass.medium().ir.at(550.0), 1.5);
+    }
+}
@@ -1,32 +1,118 @@
 use super::Material;
 
+use crate::hittable::HitRecord;
+use crate::ray::Ray;
 use crate::texture::Texture;
+use crate::texture::TexturePoint;
 
 use std::sync::Arc;
 
 use nalgebra::Vector3;
 
+// Emits light from a texture. On its own it does not scatter, but it can also be layered over a
+// base material that does, like a lit screen behind glass or an LED in plastic.
 #[derive(Clone)]
 pub struct EmissiveMaterial {
     emission: Arc<dyn Texture>,
     scale: f64,
+    base: Option<Arc<dyn Material>>,
+    one_sided: bool,
+    visible_to_camera: bool,
 }
 
 impl EmissiveMaterial {
-    pub fn arc(emission: Arc<dyn Texture>) -> Arc<EmissiveMaterial> {
-        Arc::new(EmissiveMaterial {
+    pub fn new(emission: Arc<dyn Texture>) -> EmissiveMaterial {
+        EmissiveMaterial {
             emission: emission,
             scale: 1.0,
-        })
+            base: None,
+            one_sided: false,
+            visible_to_camera: true,
+        }
+    }
+
+    pub fn arc(emission: Arc<dyn Texture>) -> Arc<EmissiveMaterial> {
+        Arc::new(EmissiveMaterial::new(emission))
+    }
+
+    pub fn arc_layered(
+        emission: Arc<dyn Texture>,
+        base: Arc<dyn Material>,
+    ) -> Arc<EmissiveMaterial> {
+        let mut material = EmissiveMaterial::new(emission);
+        material.set_base(base);
+
+        Arc::new(material)
     }
 
     pub fn set_scale(&mut self, scale: f64) {
         self.scale = scale;
     }
+
+    pub fn set_base(&mut self, base: Arc<dyn Material>) {
+        self.base = Some(base);
+    }
+
+    // Only emit from the front face of the surface.
+    pub fn set_one_sided(&mut self, one_sided: bool) {
+        self.one_sided = one_sided;
+    }
+
+    // Hidden emitters still light the scene but appear black when seen directly by the camera.
+    pub fn set_visible_to_camera(&mut self, visible_to_camera: bool) {
+        self.visible_to_camera = visible_to_camera;
+    }
 }
 
 impl Material for EmissiveMaterial {
-    fn emitted(&self, uv: (f64, f64), point: &Vector3<f64>) -> Vector3<f64> {
-        self.emission.get_color(uv, point) * self.scale
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        attenuation: &mut Vector3<f64>,
+        scattered: &mut Ray,
+    ) -> bool {
+        match &self.base {
+            Some(base) => base.scatter(ray, hit, attenuation, scattered),
+            None => false,
+        }
+    }
+
+    fn emitted(&self, ray: &Ray, hit: &HitRecord) -> Vector3<f64> {
+        let hidden =
+            (self.one_sided && !hit.front_face) || (!self.visible_to_camera && ray.bounces == 0);
+
+        let emitted = match &self.base {
+            Some(base) => base.emitted(ray, hit),
+            None => Vector3::zeros(),
+        };
+
+        if hidden {
+            return emitted;
+        }
+
+        emitted
+            + self
+                .emission
+                .get_color_filtered(hit.uv, &hit.texture_point(), &hit.footprint)
+                * self.scale
+    }
+
+    fn evaluate(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        direction: &Vector3<f64>,
+    ) -> Option<(Vector3<f64>, f64)> {
+        self.base
+            .as_ref()
+            .and_then(|base| base.evaluate(ray, hit, direction))
+    }
+
+    fn opacity(&self, uv: (f64, f64), point: &TexturePoint) -> f64 {
+        match &self.base {
+            Some(base) => base.opacity(uv, point),
+            None => 1.0,
+        }
     }
 }
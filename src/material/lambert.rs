@@ -5,6 +5,7 @@ use crate::ray::Ray;
 use crate::texture::Texture;
 use crate::vector3_traits::Helpers;
 
+use std::f64::consts::PI;
 use std::sync::Arc;
 
 use nalgebra::Vector3;
@@ -23,15 +24,35 @@ impl LambertianMaterial {
 impl Material for LambertianMaterial {
     fn scatter(
         &self,
-        _ray: &Ray,
+        ray: &Ray,
         hit: &HitRecord,
         attenuation: &mut Vector3<f64>,
         scattered: &mut Ray,
     ) -> bool {
         let scattered_direction = hit.normal + Vector3::new_random_in_unit_sphere().normalize();
-        *scattered = Ray::new(hit.point, scattered_direction);
-        *attenuation = self.albedo.get_color(hit.uv, &hit.point);
+        *scattered = ray.child(hit.point, scattered_direction);
+        *attenuation = self
+            .albedo
+            .get_color_filtered(hit.uv, &hit.texture_point(), &hit.footprint);
 
         return true;
     }
+
+    fn evaluate(
+        &self,
+        _ray: &Ray,
+        hit: &HitRecord,
+        direction: &Vector3<f64>,
+    ) -> Option<(Vector3<f64>, f64)> {
+        let cos_theta = Vector3::dot(direction, &hit.normal);
+        if cos_theta <= 0.0 || Vector3::dot(direction, &hit.geometric_normal) <= 0.0 {
+            return Some((Vector3::zeros(), 0.0));
+        }
+
+        let albedo = self
+            .albedo
+            .get_color_filtered(hit.uv, &hit.texture_point(), &hit.footprint);
+
+        Some((albedo * cos_theta / PI, cos_theta / PI))
+    }
 }
@@ -1,4 +1,5 @@
 use super::Material;
+use super::ThinFilm;
 
 use crate::hittable::HitRecord;
 use crate::ray::Ray;
@@ -13,14 +14,24 @@ use nalgebra::Vector3;
 pub struct MetalMaterial {
     albedo: Arc<dyn Texture>,
     fuzz: f64,
+    thin_film: Option<ThinFilm>,
 }
 
 impl MetalMaterial {
+    pub fn new(albedo: Arc<dyn Texture>, fuzz: f64) -> MetalMaterial {
+        MetalMaterial {
+            albedo,
+            fuzz,
+            thin_film: None,
+        }
+    }
+
     pub fn arc(albedo: Arc<dyn Texture>, fuzz: f64) -> Arc<MetalMaterial> {
-        Arc::new(MetalMaterial {
-            albedo: albedo,
-            fuzz: fuzz,
-        })
+        Arc::new(MetalMaterial::new(albedo, fuzz))
+    }
+
+    pub fn set_thin_film(&mut self, thin_film: ThinFilm) {
+        self.thin_film = Some(thin_film);
     }
 }
 
@@ -33,11 +44,34 @@ impl Material for MetalMaterial {
         scattered: &mut Ray,
     ) -> bool {
         let reflected_direction = Vector3::reflection(ray.direction.normalize(), hit.normal);
-        *scattered = Ray::new(
+        *scattered = ray.child(
             hit.point,
             reflected_direction + self.fuzz * Vector3::new_random_in_unit_sphere(),
         );
-        *attenuation = self.albedo.get_color(hit.uv, &hit.point);
+        if self.fuzz == 0.0 {
+            scattered.differential = ray.differential.and_then(|differential| {
+                differential.specular(hit, |direction| {
+                    Vector3::reflection(direction.normalize(), hit.normal)
+                })
+            });
+        }
+
+        *attenuation = self
+            .albedo
+            .get_color_filtered(hit.uv, &hit.texture_point(), &hit.footprint);
+
+        if let Some(thin_film) = &self.thin_film {
+            let cos_theta = Vector3::dot(&-ray.direction.normalize(), &hit.normal).clamp(0.0, 1.0);
+
+            *attenuation = thin_film.reflectance_over_conductor(
+                cos_theta,
+                1.0,
+                *attenuation,
+                ray.wavelength,
+                hit.uv,
+                &hit.texture_point(),
+            );
+        }
 
         return true;
     }
@@ -0,0 +1,128 @@
+use super::Material;
+
+use crate::hittable::HitRecord;
+use crate::ray::Ray;
+use crate::texture::Texture;
+use crate::texture::TexturePoint;
+
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+use rand::Rng;
+
+// Blends two materials with a weight from a texture, like rust over metal or a decal. Scattering
+// picks one of them at random with the weight as probability, which averages to the blend.
+#[derive(Clone)]
+pub struct MixMaterial {
+    first: Arc<dyn Material>,
+    second: Arc<dyn Material>,
+    // Weight of the second material in [0, 1], read as a scalar value.
+    factor: Arc<dyn Texture>,
+}
+
+impl MixMaterial {
+    pub fn arc(
+        first: Arc<dyn Material>,
+        second: Arc<dyn Material>,
+        factor: Arc<dyn Texture>,
+    ) -> Arc<MixMaterial> {
+        Arc::new(MixMaterial {
+            first,
+            second,
+            factor,
+        })
+    }
+
+    fn factor(&self, uv: (f64, f64), point: &TexturePoint) -> f64 {
+        self.factor.get_value(uv, point).clamp(0.0, 1.0)
+    }
+}
+
+impl Material for MixMaterial {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        attenuation: &mut Vector3<f64>,
+        scattered: &mut Ray,
+    ) -> bool {
+        if self.factor(hit.uv, &hit.texture_point()) > rand::thread_rng().gen() {
+            self.second.scatter(ray, hit, attenuation, scattered)
+        } else {
+            self.first.scatter(ray, hit, attenuation, scattered)
+        }
+    }
+
+    // Only evaluated when both materials can be, as a blend of their reflectances and densities.
+    fn evaluate(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        direction: &Vector3<f64>,
+    ) -> Option<(Vector3<f64>, f64)> {
+        let factor = self.factor(hit.uv, &hit.texture_point());
+
+        let (first, first_pdf) = self.first.evaluate(ray, hit, direction)?;
+        let (second, second_pdf) = self.second.evaluate(ray, hit, direction)?;
+
+        Some((
+            (1.0 - factor) * first + factor * second,
+            (1.0 - factor) * first_pdf + factor * second_pdf,
+        ))
+    }
+
+    fn emitted(&self, ray: &Ray, hit: &HitRecord) -> Vector3<f64> {
+        let factor = self.factor(hit.uv, &hit.texture_point());
+
+        (1.0 - factor) * self.first.emitted(ray, hit) + factor * self.second.emitted(ray, hit)
+    }
+
+    fn opacity(&self, uv: (f64, f64), point: &TexturePoint) -> f64 {
+        let factor = self.factor(uv, point);
+
+        (1.0 - factor) * self.first.opacity(uv, point) + factor * self.second.opacity(uv, point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::material::{LambertianMaterial, MetalMaterial};
+    use crate::texture::{SolidColorTexture, ValueTexture};
+
+    fn hit() -> HitRecord {
+        let mut hit = HitRecord::new();
+        hit.normal = Vector3::z();
+        hit.geometric_normal = Vector3::z();
+        hit.front_face = true;
+
+        hit
+    }
+
+    fn lambertian(albedo: f64) -> Arc<dyn Material> {
+        LambertianMaterial::arc(SolidColorTexture::arc(Vector3::repeat(albedo)))
+    }
+
+    #[test]
+    fn evaluate_blends_both_materials_by_the_factor() {
+        let mix = MixMaterial::arc(lambertian(0.2), lambertian(0.6), ValueTexture::arc(0.25));
+        let ray = Ray::new(Vector3::new(0.0, 0.0, 1.0), Vector3::new(0.0, 0.0, -1.0));
+        let direction = Vector3::new(0.6, 0.0, 0.8);
+
+        let (reflectance, pdf) = mix.evaluate(&ray, &hit(), &direction).unwrap();
+        let (expected, expected_pdf) = lambertian(0.3).evaluate(&ray, &hit(), &direction).unwrap();
+
+        assert!((reflectance - expected).norm() < 1e-12);
+        assert!((pdf - expected_pdf).abs() < 1e-12);
+    }
+
+    #[test]
+    fn evaluate_needs_both_materials() {
+        let metal = MetalMaterial::arc(SolidColorTexture::arc(Vector3::repeat(1.0)), 0.0);
+        let mix = MixMaterial::arc(lambertian(0.5), metal, ValueTexture::arc(0.5));
+        let ray = Ray::new(Vector3::new(0.0, 0.0, 1.0), Vector3::new(0.0, 0.0, -1.0));
+
+        assert!(mix.evaluate(&ray, &hit(), &Vector3::z()).is_none());
+    }
+}
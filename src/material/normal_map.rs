@@ -0,0 +1,97 @@
+use super::Material;
+
+use crate::hittable::HitRecord;
+use crate::ray::Ray;
+use crate::texture::Texture;
+use crate::texture::TexturePoint;
+
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+
+// Perturbs the shading normal of a material with a tangent space normal map, where the red, green
+// and blue channels hold the tangent, bitangent and normal components of the new normal.
+#[derive(Clone)]
+pub struct NormalMapMaterial {
+    material: Arc<dyn Material>,
+    normal_map: Arc<dyn Texture>,
+    strength: f64,
+}
+
+impl NormalMapMaterial {
+    // The texture holds vectors rather than colors, so an image for it has to be decoded with
+    // `ColorSpace::Linear`, as `TextureCache::arc_linear` does.
+    pub fn arc(
+        material: Arc<dyn Material>,
+        normal_map: Arc<dyn Texture>,
+        strength: f64,
+    ) -> Arc<NormalMapMaterial> {
+        Arc::new(NormalMapMaterial {
+            material,
+            normal_map,
+            strength,
+        })
+    }
+
+    fn shading_normal(&self, hit: &HitRecord) -> Vector3<f64> {
+        let color =
+            self.normal_map
+                .get_color_filtered(hit.uv, &hit.texture_point(), &hit.footprint);
+        let local = Vector3::new(
+            self.strength * (2.0 * color.x - 1.0),
+            self.strength * (2.0 * color.y - 1.0),
+            2.0 * color.z - 1.0,
+        );
+
+        let (tangent, bitangent) = hit.tangent_frame();
+        let outward = if hit.front_face {
+            hit.normal
+        } else {
+            -hit.normal
+        };
+
+        let normal = (local.x * tangent + local.y * bitangent + local.z * outward).normalize();
+        if hit.front_face {
+            normal
+        } else {
+            -normal
+        }
+    }
+}
+
+impl Material for NormalMapMaterial {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        attenuation: &mut Vector3<f64>,
+        scattered: &mut Ray,
+    ) -> bool {
+        hit.scatter_with_shading_normal(
+            &self.material,
+            self.shading_normal(hit),
+            ray,
+            attenuation,
+            scattered,
+        )
+    }
+
+    fn evaluate(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        direction: &Vector3<f64>,
+    ) -> Option<(Vector3<f64>, f64)> {
+        let shading = hit.with_shading_normal(self.shading_normal(hit), ray);
+
+        self.material.evaluate(ray, &shading, direction)
+    }
+
+    fn emitted(&self, ray: &Ray, hit: &HitRecord) -> Vector3<f64> {
+        self.material.emitted(ray, hit)
+    }
+
+    fn opacity(&self, uv: (f64, f64), point: &TexturePoint) -> f64 {
+        self.material.opacity(uv, point)
+    }
+}
@@ -0,0 +1,143 @@
+use super::Material;
+
+use crate::hittable::HitRecord;
+use crate::ray::Ray;
+use crate::texture::Texture;
+use crate::vector3_traits::Helpers;
+
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+
+// Rough diffuse surface made of V-shaped microfacets, as in clay, plaster or cloth.
+// A sigma of zero gives back the Lambertian model.
+#[derive(Clone)]
+pub struct OrenNayarMaterial {
+    albedo: Arc<dyn Texture>,
+    // Standard deviation of the facet angle in radians, read as a scalar value.
+    sigma: Arc<dyn Texture>,
+}
+
+impl OrenNayarMaterial {
+    pub fn arc(albedo: Arc<dyn Texture>, sigma: Arc<dyn Texture>) -> Arc<OrenNayarMaterial> {
+        Arc::new(OrenNayarMaterial { albedo, sigma })
+    }
+
+    fn factor(
+        sigma: f64,
+        incoming: Vector3<f64>,
+        outgoing: Vector3<f64>,
+        normal: Vector3<f64>,
+    ) -> f64 {
+        let sigma2 = sigma * sigma;
+        let a = 1.0 - 0.5 * sigma2 / (sigma2 + 0.33);
+        let b = 0.45 * sigma2 / (sigma2 + 0.09);
+
+        let cos_in = Vector3::dot(&incoming, &normal).clamp(0.0, 1.0);
+        let cos_out = Vector3::dot(&outgoing, &normal).clamp(0.0, 1.0);
+        let sin_in = (1.0 - cos_in * cos_in).sqrt();
+        let sin_out = (1.0 - cos_out * cos_out).sqrt();
+
+        let cos_phi = if sin_in > 1e-4 && sin_out > 1e-4 {
+            let tangent_in = incoming - cos_in * normal;
+            let tangent_out = outgoing - cos_out * normal;
+            (Vector3::dot(&tangent_in, &tangent_out) / (sin_in * sin_out)).max(0.0)
+        } else {
+            0.0
+        };
+
+        // sin(alpha) * tan(beta), with alpha the larger and beta the smaller of both angles.
+        let sin_alpha = sin_in.max(sin_out);
+        let tan_beta = sin_in.min(sin_out) / cos_in.max(cos_out).max(1e-4);
+
+        a + b * cos_phi * sin_alpha * tan_beta
+    }
+}
+
+impl Material for OrenNayarMaterial {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        attenuation: &mut Vector3<f64>,
+        scattered: &mut Ray,
+    ) -> bool {
+        // Cosine-weighted sampling, as for Lambertian, is the intended proposal. The roughness only
+        // scales the Lambertian lobe by a factor close to one for most directions, which is left
+        // as the weight, so the model needs no sampler of its own.
+        let scattered_direction = hit.normal + Vector3::new_random_in_unit_sphere().normalize();
+        *scattered = ray.child(hit.point, scattered_direction);
+
+        let sigma = self.sigma.get_value(hit.uv, &hit.texture_point());
+        let factor = OrenNayarMaterial::factor(
+            sigma,
+            -ray.direction.normalize(),
+            scattered_direction.normalize(),
+            hit.normal,
+        );
+
+        *attenuation = self
+            .albedo
+            .get_color_filtered(hit.uv, &hit.texture_point(), &hit.footprint)
+            * factor;
+
+        true
+    }
+
+    fn evaluate(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        direction: &Vector3<f64>,
+    ) -> Option<(Vector3<f64>, f64)> {
+        let cos_theta = Vector3::dot(direction, &hit.normal);
+        if cos_theta <= 0.0 || Vector3::dot(direction, &hit.geometric_normal) <= 0.0 {
+            return Some((Vector3::zeros(), 0.0));
+        }
+
+        let sigma = self.sigma.get_value(hit.uv, &hit.texture_point());
+        let factor =
+            OrenNayarMaterial::factor(sigma, -ray.direction.normalize(), *direction, hit.normal);
+        let albedo = self
+            .albedo
+            .get_color_filtered(hit.uv, &hit.texture_point(), &hit.footprint);
+
+        Some((albedo * factor * cos_theta / PI, cos_theta / PI))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::material::LambertianMaterial;
+    use crate::texture::{SolidColorTexture, ValueTexture};
+
+    #[test]
+    fn zero_sigma_evaluates_as_lambertian() {
+        let albedo = SolidColorTexture::arc(Vector3::new(0.8, 0.5, 0.2));
+        let rough = OrenNayarMaterial::arc(albedo.clone(), ValueTexture::arc(0.0));
+        let lambertian = LambertianMaterial::arc(albedo);
+
+        let mut hit = HitRecord::new();
+        hit.normal = Vector3::z();
+        hit.geometric_normal = Vector3::z();
+        hit.front_face = true;
+
+        let ray = Ray::new(Vector3::new(1.0, 0.0, 1.0), Vector3::new(-1.0, 0.0, -1.0));
+
+        for direction in [
+            Vector3::z(),
+            Vector3::new(0.6, 0.0, 0.8),
+            Vector3::new(-0.6, 0.0, 0.8),
+            Vector3::new(0.0, 0.8, 0.6),
+        ] {
+            let (reflectance, pdf) = rough.evaluate(&ray, &hit, &direction).unwrap();
+            let (expected, expected_pdf) = lambertian.evaluate(&ray, &hit, &direction).unwrap();
+
+            assert!((reflectance - expected).norm() < 1e-12);
+            assert!((pdf - expected_pdf).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::spectrum::WAVELENGTH_D;
+
+#[derive(Copy, Clone)]
+pub enum RefractiveIndex {
+    Constant(f64),
+    // n = a + b / λ², with λ in micrometers.
+    Cauchy { a: f64, b: f64 },
+    // n² = 1 + Σ b λ² / (λ² - c), with λ in micrometers.
+    Sellmeier { b: [f64; 3], c: [f64; 3] },
+}
+
+impl RefractiveIndex {
+    pub const BK7: RefractiveIndex = RefractiveIndex::Sellmeier {
+        b: [1.03961212, 0.231792344, 1.01046945],
+        c: [0.00600069867, 0.0200179144, 103.560653],
+    };
+
+    pub const DIAMOND: RefractiveIndex = RefractiveIndex::Sellmeier {
+        b: [4.3356, 0.3306, 0.0],
+        c: [0.106 * 0.106, 0.175 * 0.175, 0.0],
+    };
+
+    // Cauchy fit through the index at the d line and the Abbe number, using the F and C lines.
+    pub fn abbe(ir: f64, abbe_number: f64) -> RefractiveIndex {
+        let (d, f, c) = (WAVELENGTH_D / 1000.0, 0.48613, 0.65627);
+
+        let b = (ir - 1.0) / abbe_number / (1.0 / (f * f) - 1.0 / (c * c));
+        let a = ir - b / (d * d);
+
+        RefractiveIndex::Cauchy { a, b }
+    }
+
+    pub fn is_dispersive(&self) -> bool {
+        !matches!(self, RefractiveIndex::Constant(_))
+    }
+
+    pub fn at(&self, wavelength: f64) -> f64 {
+        let micrometers = wavelength / 1000.0;
+        let squared = micrometers * micrometers;
+
+        match self {
+            RefractiveIndex::Constant(ir) => *ir,
+            RefractiveIndex::Cauchy { a, b } => a + b / squared,
+            RefractiveIndex::Sellmeier { b, c } => (1.0
+                + (0..3)
+                    .map(|i| b[i] * squared / (squared - c[i]))
+                    .sum::<f64>())
+            .sqrt(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WAVELENGTH_F: f64 = 486.13;
+    const WAVELENGTH_C: f64 = 656.27;
+
+    fn abbe_number(ir: &RefractiveIndex) -> f64 {
+        (ir.at(WAVELENGTH_D) - 1.0) / (ir.at(WAVELENGTH_F) - ir.at(WAVELENGTH_C))
+    }
+
+    #[test]
+    fn abbe_fit_keeps_the_index_and_the_abbe_number() {
+        let ir = RefractiveIndex::abbe(1.5168, 64.17);
+
+        assert!((ir.at(WAVELENGTH_D) - 1.5168).abs() < 1e-9);
+        assert!((abbe_number(&ir) - 64.17).abs() < 1e-6);
+        assert!(ir.at(WAVELENGTH_F) > ir.at(WAVELENGTH_C));
+    }
+
+    #[test]
+    fn sellmeier_glasses_match_their_catalog_values() {
+        assert!((RefractiveIndex::BK7.at(WAVELENGTH_D) - 1.5168).abs() < 1e-3);
+        assert!((abbe_number(&RefractiveIndex::BK7) - 64.17).abs() < 0.5);
+        assert!((RefractiveIndex::DIAMOND.at(WAVELENGTH_D) - 2.417).abs() < 1e-2);
+    }
+
+    #[test]
+    fn constant_index_does_not_disperse() {
+        let ir = RefractiveIndex::Constant(1.5);
+
+        assert!(!ir.is_dispersive());
+        assert_eq!(ir.at(WAVELENGTH_F), ir.at(WAVELENGTH_C));
+        assert!(RefractiveIndex::abbe(1.5, 40.0).is_dispersive());
+    }
+}
@@ -0,0 +1,241 @@
+use super::Material;
+
+use crate::hittable::HitRecord;
+use crate::ray::Ray;
+use crate::texture::Texture;
+use crate::texture::TexturePoint;
+use crate::vector3_traits::Helpers;
+
+use std::f64::consts::PI;
+use std::sync::Arc;
+use std::sync::OnceLock;
+
+use nalgebra::Vector3;
+use rand::Rng;
+
+const ALBEDO_TABLE_SIZE: usize = 16;
+
+// Cloth and velvet: the Charlie sheen distribution with Neubelt's visibility term, on its own or
+// as a layer over a base material. The base only receives the energy the sheen does not reflect.
+#[derive(Clone)]
+pub struct SheenMaterial {
+    color: Arc<dyn Texture>,
+    // Roughness in [0, 1], read as a scalar value.
+    roughness: Arc<dyn Texture>,
+    base: Option<Arc<dyn Material>>,
+}
+
+impl SheenMaterial {
+    pub fn arc(color: Arc<dyn Texture>, roughness: Arc<dyn Texture>) -> Arc<SheenMaterial> {
+        Arc::new(SheenMaterial {
+            color,
+            roughness,
+            base: None,
+        })
+    }
+
+    pub fn arc_layered(
+        color: Arc<dyn Texture>,
+        roughness: Arc<dyn Texture>,
+        base: Arc<dyn Material>,
+    ) -> Arc<SheenMaterial> {
+        Arc::new(SheenMaterial {
+            color,
+            roughness,
+            base: Some(base),
+        })
+    }
+
+    fn alpha(roughness: f64) -> f64 {
+        (roughness * roughness).clamp(0.01, 1.0)
+    }
+
+    // Distribution times visibility, for unit vectors in the frame where z is the normal.
+    fn lobe(alpha: f64, view: Vector3<f64>, light: Vector3<f64>) -> f64 {
+        let half = (view + light).normalize();
+        let sin_half = (1.0 - half.z * half.z).max(0.0).sqrt();
+
+        let distribution = (2.0 + 1.0 / alpha) * sin_half.powf(1.0 / alpha) / (2.0 * PI);
+        let visibility = 1.0 / (4.0 * (light.z + view.z - light.z * view.z)).max(1e-4);
+
+        distribution * visibility
+    }
+
+    // Fraction of light reflected by the sheen lobe, interpolated from a table integrated once.
+    fn directional_albedo(cos_view: f64, alpha: f64) -> f64 {
+        static TABLE: OnceLock<Vec<f64>> = OnceLock::new();
+
+        let table = TABLE.get_or_init(|| {
+            let (steps_theta, steps_phi) = (32, 64);
+            let (d_theta, d_phi) = (0.5 * PI / steps_theta as f64, 2.0 * PI / steps_phi as f64);
+
+            let mut table = Vec::with_capacity(ALBEDO_TABLE_SIZE * ALBEDO_TABLE_SIZE);
+            for i in 0..ALBEDO_TABLE_SIZE {
+                let alpha = SheenMaterial::table_value(i).max(0.01);
+
+                for j in 0..ALBEDO_TABLE_SIZE {
+                    let cos_view = SheenMaterial::table_value(j).max(1e-3);
+                    let view = Vector3::new((1.0 - cos_view * cos_view).sqrt(), 0.0, cos_view);
+
+                    let mut albedo = 0.0;
+                    for t in 0..steps_theta {
+                        let theta = (t as f64 + 0.5) * d_theta;
+                        for p in 0..steps_phi {
+                            let phi = (p as f64 + 0.5) * d_phi;
+                            let light = Vector3::new(
+                                theta.sin() * phi.cos(),
+                                theta.sin() * phi.sin(),
+                                theta.cos(),
+                            );
+
+                            albedo += SheenMaterial::lobe(alpha, view, light)
+                                * theta.cos()
+                                * theta.sin()
+                                * d_theta
+                                * d_phi;
+                        }
+                    }
+
+                    table.push(albedo.min(1.0));
+                }
+            }
+
+            table
+        });
+
+        let lookup = |value: f64| {
+            let position = value.clamp(0.0, 1.0) * (ALBEDO_TABLE_SIZE - 1) as f64;
+            let index = (position as usize).min(ALBEDO_TABLE_SIZE - 2);
+            (index, position - index as f64)
+        };
+
+        let (i, fi) = lookup(alpha);
+        let (j, fj) = lookup(cos_view);
+        let at = |i: usize, j: usize| table[i * ALBEDO_TABLE_SIZE + j];
+
+        (1.0 - fi) * ((1.0 - fj) * at(i, j) + fj * at(i, j + 1))
+            + fi * ((1.0 - fj) * at(i + 1, j) + fj * at(i + 1, j + 1))
+    }
+
+    // Unit direction in the tangent frame of the hit, where z is the shading normal.
+    fn to_local(hit: &HitRecord, direction: Vector3<f64>) -> Vector3<f64> {
+        let (tangent, bitangent) = hit.tangent_frame();
+
+        Vector3::new(
+            Vector3::dot(&direction, &tangent),
+            Vector3::dot(&direction, &bitangent),
+            Vector3::dot(&direction, &hit.normal),
+        )
+    }
+
+    fn table_value(index: usize) -> f64 {
+        index as f64 / (ALBEDO_TABLE_SIZE - 1) as f64
+    }
+}
+
+impl Material for SheenMaterial {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        attenuation: &mut Vector3<f64>,
+        scattered: &mut Ray,
+    ) -> bool {
+        let color = self
+            .color
+            .get_color_filtered(hit.uv, &hit.texture_point(), &hit.footprint);
+        let alpha = SheenMaterial::alpha(self.roughness.get_value(hit.uv, &hit.texture_point()));
+
+        let view = -ray.direction.normalize();
+        let cos_view = Vector3::dot(&view, &hit.normal).clamp(0.0, 1.0);
+
+        // With a base, both lobes are picked with equal probability.
+        let sheen_probability = if self.base.is_some() { 0.5 } else { 1.0 };
+
+        if let Some(base) = &self.base {
+            if rand::thread_rng().gen::<f64>() >= sheen_probability {
+                if !base.scatter(ray, hit, attenuation, scattered) {
+                    return false;
+                }
+
+                let albedo = SheenMaterial::directional_albedo(cos_view, alpha);
+                let remaining = Vector3::repeat(1.0) - color * albedo;
+                *attenuation = attenuation.component_mul(&remaining) / (1.0 - sheen_probability);
+
+                return true;
+            }
+        }
+
+        let scattered_direction = hit.normal + Vector3::new_random_in_unit_sphere().normalize();
+        *scattered = ray.child(hit.point, scattered_direction);
+
+        let light = SheenMaterial::to_local(hit, scattered_direction.normalize());
+        if light.z <= 0.0 {
+            return false;
+        }
+
+        // Cosine-weighted sampling cancels the cosine, leaving pi times the lobe.
+        let lobe = SheenMaterial::lobe(alpha, SheenMaterial::to_local(hit, view), light);
+        *attenuation = color * (PI * lobe / sheen_probability);
+
+        true
+    }
+
+    // Both lobes are evaluated with the weights and densities `scatter` picks them with, so a base
+    // that cannot be evaluated leaves the whole material unevaluated.
+    fn evaluate(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        direction: &Vector3<f64>,
+    ) -> Option<(Vector3<f64>, f64)> {
+        let base = match &self.base {
+            Some(base) => Some(base.evaluate(ray, hit, direction)?),
+            None => None,
+        };
+
+        let color = self
+            .color
+            .get_color_filtered(hit.uv, &hit.texture_point(), &hit.footprint);
+        let alpha = SheenMaterial::alpha(self.roughness.get_value(hit.uv, &hit.texture_point()));
+
+        let view = -ray.direction.normalize();
+        let light = SheenMaterial::to_local(hit, *direction);
+
+        let (sheen, sheen_pdf) =
+            if light.z <= 0.0 || Vector3::dot(direction, &hit.geometric_normal) <= 0.0 {
+                (Vector3::zeros(), 0.0)
+            } else {
+                let lobe = SheenMaterial::lobe(alpha, SheenMaterial::to_local(hit, view), light);
+                (color * lobe * light.z, light.z / PI)
+            };
+
+        match base {
+            Some((reflectance, pdf)) => {
+                let cos_view = Vector3::dot(&view, &hit.normal).clamp(0.0, 1.0);
+                let albedo = SheenMaterial::directional_albedo(cos_view, alpha);
+                let remaining = Vector3::repeat(1.0) - color * albedo;
+
+                Some((
+                    sheen + remaining.component_mul(&reflectance),
+                    0.5 * sheen_pdf + 0.5 * pdf,
+                ))
+            }
+            None => Some((sheen, sheen_pdf)),
+        }
+    }
+
+    fn emitted(&self, ray: &Ray, hit: &HitRecord) -> Vector3<f64> {
+        match &self.base {
+            Some(base) => base.emitted(ray, hit),
+            None => Vector3::zeros(),
+        }
+    }
+
+    fn opacity(&self, uv: (f64, f64), point: &TexturePoint) -> f64 {
+        match &self.base {
+            Some(base) => base.opacity(uv, point),
+            None => 1.0,
+        }
+    }
+}
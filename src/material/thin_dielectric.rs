@@ -0,0 +1,64 @@
+use super::DielectricMaterial;
+use super::Material;
+
+use crate::hittable::HitRecord;
+use crate::ray::Ray;
+use crate::vector3_traits::Helpers;
+
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+use rand::Rng;
+
+// Infinitely thin slab of dielectric for single sided surfaces, like a window pane. Light bouncing
+// between both parallel interfaces is summed analytically, and transmitted light leaves in its
+// original direction since the two refractions cancel out.
+#[derive(Clone)]
+pub struct ThinDielectricMaterial {
+    ir: f64,
+}
+
+impl ThinDielectricMaterial {
+    pub fn arc(ir: f64) -> Arc<ThinDielectricMaterial> {
+        Arc::new(ThinDielectricMaterial { ir })
+    }
+}
+
+impl Material for ThinDielectricMaterial {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        attenuation: &mut Vector3<f64>,
+        scattered: &mut Ray,
+    ) -> bool {
+        *attenuation = Vector3::new(1.0, 1.0, 1.0);
+
+        let direction_normalized = ray.direction.normalize();
+        let cos_theta = f64::min(Vector3::dot(&-direction_normalized, &hit.normal), 1.0);
+
+        // R + T²R / (1 - R²) for the geometric series of internal bounces.
+        let single = DielectricMaterial::reflectance(cos_theta, 1.0 / self.ir);
+        let reflectance = 2.0 * single / (1.0 + single);
+
+        let reflected = reflectance > rand::thread_rng().gen();
+        let direction = if reflected {
+            Vector3::reflection(direction_normalized, hit.normal)
+        } else {
+            ray.direction
+        };
+
+        *scattered = ray.child(hit.point, direction);
+        scattered.differential = ray.differential.and_then(|differential| {
+            differential.specular(hit, |direction| {
+                if reflected {
+                    Vector3::reflection(direction.normalize(), hit.normal)
+                } else {
+                    direction
+                }
+            })
+        });
+
+        true
+    }
+}
@@ -0,0 +1,114 @@
+use crate::texture::Texture;
+use crate::texture::TexturePoint;
+
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+
+// Wavelengths in nanometers used for the red, green and blue channels.
+const CHANNEL_WAVELENGTHS: [f64; 3] = [630.0, 532.0, 465.0];
+
+// Thin transparent layer on top of a surface, like soap, oil or a lens coating. Light reflected at
+// both sides of the layer interferes, which is computed with the Airy formula for each channel, or
+// for the path's wavelength once it has one.
+#[derive(Clone)]
+pub struct ThinFilm {
+    // Thickness in nanometers, read as a scalar value.
+    thickness: Arc<dyn Texture>,
+    ir: f64,
+}
+
+impl ThinFilm {
+    pub fn new(thickness: Arc<dyn Texture>, ir: f64) -> ThinFilm {
+        ThinFilm { thickness, ir }
+    }
+
+    // Reflectance of the film between the incident medium and a dielectric substrate.
+    pub fn reflectance(
+        &self,
+        cos_theta: f64,
+        incident_ir: f64,
+        substrate_ir: f64,
+        wavelength: Option<f64>,
+        uv: (f64, f64),
+        point: &TexturePoint,
+    ) -> Vector3<f64> {
+        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+
+        let sin_film = incident_ir / self.ir * sin_theta;
+        let sin_substrate = incident_ir / substrate_ir * sin_theta;
+        if sin_film >= 1.0 || sin_substrate >= 1.0 {
+            return Vector3::new(1.0, 1.0, 1.0);
+        }
+
+        let cos_film = (1.0 - sin_film * sin_film).sqrt();
+        let cos_substrate = (1.0 - sin_substrate * sin_substrate).sqrt();
+
+        let (n1, n2, n3) = (incident_ir, self.ir, substrate_ir);
+
+        let r12_s = (n1 * cos_theta - n2 * cos_film) / (n1 * cos_theta + n2 * cos_film);
+        let r23_s = (n2 * cos_film - n3 * cos_substrate) / (n2 * cos_film + n3 * cos_substrate);
+        let r12_p = (n2 * cos_theta - n1 * cos_film) / (n2 * cos_theta + n1 * cos_film);
+        let r23_p = (n3 * cos_film - n2 * cos_substrate) / (n3 * cos_film + n2 * cos_substrate);
+
+        self.interfere(cos_film, wavelength, uv, point, |_, phase| {
+            0.5 * (ThinFilm::airy(r12_s, r23_s, phase) + ThinFilm::airy(r12_p, r23_p, phase))
+        })
+    }
+
+    // Reflectance of the film over a conductor whose own reflectance is known per channel. The
+    // conductor is approximated as reflecting with a phase shift of half a wave.
+    pub fn reflectance_over_conductor(
+        &self,
+        cos_theta: f64,
+        incident_ir: f64,
+        substrate_reflectance: Vector3<f64>,
+        wavelength: Option<f64>,
+        uv: (f64, f64),
+        point: &TexturePoint,
+    ) -> Vector3<f64> {
+        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+
+        let sin_film = incident_ir / self.ir * sin_theta;
+        if sin_film >= 1.0 {
+            return Vector3::new(1.0, 1.0, 1.0);
+        }
+
+        let cos_film = (1.0 - sin_film * sin_film).sqrt();
+
+        let (n1, n2) = (incident_ir, self.ir);
+
+        let r12_s = (n1 * cos_theta - n2 * cos_film) / (n1 * cos_theta + n2 * cos_film);
+        let r12_p = (n2 * cos_theta - n1 * cos_film) / (n2 * cos_theta + n1 * cos_film);
+
+        self.interfere(cos_film, wavelength, uv, point, |channel, phase| {
+            let r23 = -substrate_reflectance[channel].clamp(0.0, 1.0).sqrt();
+
+            0.5 * (ThinFilm::airy(r12_s, r23, phase) + ThinFilm::airy(r12_p, r23, phase))
+        })
+    }
+
+    fn interfere(
+        &self,
+        cos_film: f64,
+        wavelength: Option<f64>,
+        uv: (f64, f64),
+        point: &TexturePoint,
+        airy: impl Fn(usize, f64) -> f64,
+    ) -> Vector3<f64> {
+        let thickness = self.thickness.get_value(uv, point);
+        let optical_path = 4.0 * PI * self.ir * thickness * cos_film;
+
+        Vector3::from_fn(|channel, _| {
+            let wavelength = wavelength.unwrap_or(CHANNEL_WAVELENGTHS[channel]);
+            airy(channel, optical_path / wavelength)
+        })
+    }
+
+    fn airy(r12: f64, r23: f64, phase: f64) -> f64 {
+        let cross = 2.0 * r12 * r23 * phase.cos();
+
+        (r12 * r12 + r23 * r23 + cross) / (1.0 + r12 * r12 * r23 * r23 + cross)
+    }
+}
@@ -0,0 +1,64 @@
+use super::Material;
+
+use crate::hittable::HitRecord;
+use crate::ray::Ray;
+use crate::texture::Texture;
+use crate::vector3_traits::Helpers;
+
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+use rand::Rng;
+
+// Thin diffuse surface that lets part of the light through to the other side, like leaves, paper
+// or lampshades. The transmittance texture sets, per channel, how much of the albedo is
+// transmitted rather than reflected.
+#[derive(Clone)]
+pub struct TranslucentMaterial {
+    albedo: Arc<dyn Texture>,
+    transmittance: Arc<dyn Texture>,
+}
+
+impl TranslucentMaterial {
+    pub fn arc(
+        albedo: Arc<dyn Texture>,
+        transmittance: Arc<dyn Texture>,
+    ) -> Arc<TranslucentMaterial> {
+        Arc::new(TranslucentMaterial {
+            albedo,
+            transmittance,
+        })
+    }
+}
+
+impl Material for TranslucentMaterial {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        attenuation: &mut Vector3<f64>,
+        scattered: &mut Ray,
+    ) -> bool {
+        let albedo = self
+            .albedo
+            .get_color_filtered(hit.uv, &hit.texture_point(), &hit.footprint);
+        let transmittance = self
+            .transmittance
+            .get_color_filtered(hit.uv, &hit.texture_point(), &hit.footprint)
+            .map(|t| t.clamp(0.0, 1.0));
+
+        let transmit_probability = transmittance.mean().clamp(0.0, 1.0);
+        let random_direction = Vector3::new_random_in_unit_sphere().normalize();
+
+        if transmit_probability > rand::thread_rng().gen() {
+            *scattered = ray.child(hit.point, -hit.normal + random_direction);
+            *attenuation = albedo.component_mul(&transmittance) / transmit_probability;
+        } else {
+            let reflectance = Vector3::repeat(1.0) - transmittance;
+            *scattered = ray.child(hit.point, hit.normal + random_direction);
+            *attenuation = albedo.component_mul(&reflectance) / (1.0 - transmit_probability);
+        }
+
+        true
+    }
+}
@@ -4,30 +4,37 @@ use std::fs;
 
 pub struct ObjLoader {}
 
+// Position, normal and texture coordinates of one corner of a face.
+pub type Corner = (Vector3<f64>, Vector3<f64>, Option<(f64, f64)>);
+
 pub struct Obj {
-    pub faces: Vec<(Vector3<f64>, Vector3<f64>)>,
+    // Three corners per triangle.
+    pub faces: Vec<Corner>,
 }
 
 impl ObjLoader {
     pub fn load(file_path: String) -> std::io::Result<Obj> {
         let contents = fs::read_to_string(file_path)?;
 
+        Ok(ObjLoader::parse(&contents))
+    }
+
+    pub fn parse(contents: &str) -> Obj {
         let mut vertices = Vec::<Vector3<f64>>::new();
         let mut normals = Vec::<Vector3<f64>>::new();
-        let mut faces = Vec::<(Vector3<f64>, Vector3<f64>)>::new();
+        let mut uvs = Vec::<(f64, f64)>::new();
+        let mut faces = Vec::<Corner>::new();
 
         vertices.push(Vector3::zeros());
         normals.push(Vector3::zeros());
+        uvs.push((0.0, 0.0));
 
-        let lines = contents.split("\n");
-
-        for line in lines {
-            if line.is_empty() {
+        for line in contents.lines() {
+            let elems = line.split_whitespace().collect::<Vec<&str>>();
+            if elems.is_empty() {
                 continue;
             }
 
-            let elems = line.split(" ").collect::<Vec<&str>>();
-
             match elems[0] {
                 "#" => continue,
                 "v" => vertices.push(Vector3::new(
@@ -40,19 +47,137 @@ impl ObjLoader {
                     elems[2].parse().unwrap(),
                     elems[3].parse().unwrap(),
                 )),
+                // The second coordinate is optional and defaults to zero.
+                "vt" => uvs.push((
+                    elems[1].parse().unwrap(),
+                    elems.get(2).map_or(0.0, |v| v.parse().unwrap()),
+                )),
                 "s" => continue,
                 "f" => {
-                    for i in 1..=3 {
-                        let pair: Vec<&str> = elems[i].split("/").collect();
-                        let (vertex_index, normal_index): (usize, usize) =
-                            (pair[0].parse().unwrap(), pair[2].parse().unwrap());
-                        faces.push((vertices[vertex_index], normals[normal_index]));
+                    // Corners are `v`, `v/vt`, `v//vn` or `v/vt/vn`, and negative indices count
+                    // back from the last element read so far.
+                    let corners = elems[1..]
+                        .iter()
+                        .map(|corner| {
+                            let indices = corner
+                                .split("/")
+                                .map(|index| index.parse::<isize>().ok())
+                                .collect::<Vec<Option<isize>>>();
+                            let index = |i: usize, count: usize| {
+                                indices.get(i).copied().flatten().map(|index| {
+                                    if index < 0 {
+                                        (count as isize + index) as usize
+                                    } else {
+                                        index as usize
+                                    }
+                                })
+                            };
+
+                            (
+                                vertices[index(0, vertices.len()).unwrap()],
+                                index(2, normals.len()).map(|normal_index| normals[normal_index]),
+                                index(1, uvs.len()).map(|uv_index| uvs[uv_index]),
+                            )
+                        })
+                        .collect::<Vec<_>>();
+
+                    // Polygons are split into a fan of triangles around their first corner.
+                    for i in 1..corners.len().saturating_sub(1) {
+                        let triangle = [corners[0], corners[i], corners[i + 1]];
+
+                        // Corners without a normal take the one of the flat triangle.
+                        let flat_normal = Vector3::cross(
+                            &(triangle[1].0 - triangle[0].0),
+                            &(triangle[2].0 - triangle[0].0),
+                        )
+                        .normalize();
+
+                        for (vertex, normal, uv) in triangle {
+                            faces.push((vertex, normal.unwrap_or(flat_normal), uv));
+                        }
                     }
                 }
                 _ => continue,
             }
         }
 
-        Ok(Obj { faces })
+        Obj { faces }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0.25 0.5\nvt 0.75\nvt 1 1\nvn 0 0 -1\n";
+
+    fn faces(face: &str) -> Vec<Corner> {
+        ObjLoader::parse(&format!("{HEADER}{face}\n")).faces
+    }
+
+    #[test]
+    fn parses_positions_only() {
+        let faces = faces("f 1 2 3");
+
+        assert_eq!(faces.len(), 3);
+        assert_eq!(faces[1].0, Vector3::new(1.0, 0.0, 0.0));
+        assert!(faces.iter().all(|corner| corner.1 == Vector3::z()));
+        assert!(faces.iter().all(|corner| corner.2.is_none()));
+    }
+
+    #[test]
+    fn parses_texture_coordinates_without_normals() {
+        let faces = faces("f 1/1 2/2 3/3");
+
+        assert!(faces.iter().all(|corner| corner.1 == Vector3::z()));
+        assert_eq!(faces[0].2, Some((0.25, 0.5)));
+        assert_eq!(faces[1].2, Some((0.75, 0.0)));
+        assert_eq!(faces[2].2, Some((1.0, 1.0)));
+    }
+
+    #[test]
+    fn parses_normals_without_texture_coordinates() {
+        let faces = faces("f 1//1 2//1  3//1");
+
+        assert!(faces.iter().all(|corner| corner.1 == -Vector3::z()));
+        assert!(faces.iter().all(|corner| corner.2.is_none()));
+    }
+
+    #[test]
+    fn splits_polygons_into_triangle_fans() {
+        let faces = faces("v 1 1 0\nv 0.5 1.5 0\nf 1 2 4 5 3");
+        let positions = faces.iter().map(|corner| corner.0).collect::<Vec<_>>();
+
+        let vertex = |x, y| Vector3::new(x, y, 0.0);
+        let (first, second, fourth, fifth, third) = (
+            vertex(0.0, 0.0),
+            vertex(1.0, 0.0),
+            vertex(1.0, 1.0),
+            vertex(0.5, 1.5),
+            vertex(0.0, 1.0),
+        );
+
+        assert_eq!(
+            positions,
+            [first, second, fourth, first, fourth, fifth, first, fifth, third]
+        );
+        assert!(faces.iter().all(|corner| corner.1 == Vector3::z()));
+    }
+
+    #[test]
+    fn resolves_negative_indices_from_the_end() {
+        assert_eq!(
+            faces("f -3/-3/-1 -2/-2/-1 -1/-1/-1"),
+            faces("f 1/1/1 2/2/1 3/3/1")
+        );
+    }
+
+    #[test]
+    fn parses_texture_coordinates_and_normals() {
+        let faces = faces("f 1/1/1 2/2/1 3/3/1");
+
+        assert_eq!(faces[2].0, Vector3::new(0.0, 1.0, 0.0));
+        assert!(faces.iter().all(|corner| corner.1 == -Vector3::z()));
+        assert_eq!(faces[1].2, Some((0.75, 0.0)));
     }
 }
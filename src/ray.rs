@@ -1,18 +1,61 @@
+use crate::differential::RayDifferential;
+use crate::environment::Environment;
 use crate::hittable::{HitRecord, Hittable};
+use crate::interior::Interior;
+use crate::vector3_traits::Helpers;
 
 use std::sync::Arc;
 
 use nalgebra::Vector3;
+use rand::Rng;
+
+const MAX_MEDIUM_STEPS: u32 = 256;
 
 #[derive(Copy, Clone)]
 pub struct Ray {
     pub origin: Vector3<f64>,
     pub direction: Vector3<f64>,
+    // Wavelength in nanometers, once a dispersive material has picked one for this path.
+    pub wavelength: Option<f64>,
+    pub interior: Interior,
+    // Number of times the path has scattered, which is zero for camera rays.
+    pub bounces: u32,
+    // Offset rays through the neighbouring pixels, only kept along perfectly specular paths.
+    pub differential: Option<RayDifferential>,
+    // Density with which the last bounce picked this direction, when that bounce also sampled the
+    // environment directly. Weights the environment where the ray leaves the scene.
+    pub scatter_pdf: Option<f64>,
+    // Origin and direction before the transforms above the shape being tested moved the ray, which
+    // keep the same distances along it.
+    pub world: Option<(Vector3<f64>, Vector3<f64>)>,
 }
 
 impl Ray {
     pub fn new(origin: Vector3<f64>, direction: Vector3<f64>) -> Ray {
-        Ray { origin, direction }
+        Ray {
+            origin,
+            direction,
+            wavelength: None,
+            interior: Interior::new(),
+            bounces: 0,
+            differential: None,
+            scatter_pdf: None,
+            world: None,
+        }
+    }
+
+    // Continues the path of this ray from a new origin, keeping its per-path state.
+    pub fn child(&self, origin: Vector3<f64>, direction: Vector3<f64>) -> Ray {
+        Ray {
+            origin,
+            direction,
+            wavelength: self.wavelength,
+            interior: self.interior,
+            bounces: self.bounces + 1,
+            differential: None,
+            scatter_pdf: None,
+            world: None,
+        }
     }
 
     pub fn zeros() -> Ray {
@@ -23,10 +66,19 @@ impl Ray {
         self.origin + t * self.direction
     }
 
+    // Point at the given distance along the ray in world space.
+    pub fn world_at(&self, t: f64) -> Vector3<f64> {
+        match self.world {
+            Some((origin, direction)) => origin + t * direction,
+            None => self.at(t),
+        }
+    }
+
     pub fn ray_color(
         ray: &Ray,
         world: &Arc<dyn Hittable>,
         background_color: &Vector3<f64>,
+        environment: Option<&dyn Environment>,
         depth: u64,
     ) -> Vector3<f64> {
         let mut hit = HitRecord::new();
@@ -35,24 +87,164 @@ impl Ray {
             return Vector3::zeros();
         }
 
-        if world.hit(ray, 0.0001, f64::MAX, &mut hit) == false {
-            return *background_color;
+        let mut ray = *ray;
+        let mut throughput = Vector3::new(1.0, 1.0, 1.0);
+
+        if !Self::hit_through_interior(&mut ray, world, &mut hit, &mut throughput) {
+            let background = match environment {
+                Some(environment) => Self::environment_radiance(&ray, environment),
+                None => *background_color,
+            };
+
+            return throughput.component_mul(&background);
+        }
+
+        if let Some(differential) = ray.differential {
+            hit.footprint = differential.footprint(&hit);
         }
 
         let mut scattered = Ray::zeros();
         let mut attenuation = Vector3::zeros();
-        let emitted = hit.material.emitted(hit.uv, &hit.point);
+        let emitted = hit.material.emitted(&ray, &hit);
 
         if hit
             .material
             .scatter(&ray, &hit, &mut attenuation, &mut scattered)
             == false
         {
-            return emitted;
+            return throughput.component_mul(&emitted);
+        }
+
+        // Light sampling is left out inside media, where shadow rays would skip the attenuation.
+        let mut direct = Vector3::zeros();
+        if let Some(environment) = environment {
+            if ray.interior.current().is_none() {
+                direct = Self::sample_environment(&ray, &hit, world, environment, &mut scattered);
+            }
+        }
+
+        let scatter = &Self::ray_color(&scattered, world, background_color, environment, depth - 1);
+
+        return throughput.component_mul(&(emitted + direct + attenuation.component_mul(scatter)));
+    }
+
+    // Radiance of the environment where a ray leaves the scene. When the last bounce also sampled
+    // the environment directly, both estimates are combined with multiple importance sampling.
+    fn environment_radiance(ray: &Ray, environment: &dyn Environment) -> Vector3<f64> {
+        let direction = ray.direction.normalize();
+        let radiance = environment.radiance(&direction);
+
+        match ray.scatter_pdf {
+            Some(scatter_pdf) => {
+                radiance * power_heuristic(scatter_pdf, environment.pdf(&direction))
+            }
+            None => radiance,
+        }
+    }
+
+    // Light reaching the hit directly from a direction picked on the environment, for materials
+    // that can be evaluated in any direction. Marks the scattered ray for weighting on a miss.
+    fn sample_environment(
+        ray: &Ray,
+        hit: &HitRecord,
+        world: &Arc<dyn Hittable>,
+        environment: &dyn Environment,
+        scattered: &mut Ray,
+    ) -> Vector3<f64> {
+        let scatter_pdf = match hit
+            .material
+            .evaluate(ray, hit, &scattered.direction.normalize())
+        {
+            Some((_, pdf)) => pdf,
+            None => return Vector3::zeros(),
+        };
+        scattered.scatter_pdf = Some(scatter_pdf);
+
+        let (direction, light_pdf) = environment.sample();
+        if light_pdf <= 0.0 {
+            return Vector3::zeros();
+        }
+
+        let (reflectance, pdf) = match hit.material.evaluate(ray, hit, &direction) {
+            Some(evaluated) => evaluated,
+            None => return Vector3::zeros(),
+        };
+        if reflectance == Vector3::zeros() {
+            return Vector3::zeros();
+        }
+
+        let shadow = ray.child(hit.point, direction);
+        if world.hit(&shadow, 0.0001, f64::MAX, &mut HitRecord::new()) {
+            return Vector3::zeros();
+        }
+
+        reflectance.component_mul(&environment.radiance(&direction))
+            * power_heuristic(light_pdf, pdf)
+            / light_pdf
+    }
+
+    // Finds the next surface along the ray, accounting for the medium the path is inside of.
+    // Absorbing media attenuate the throughput, while scattering media are crossed with a random
+    // walk that moves the ray to where it finally reaches a surface.
+    fn hit_through_interior(
+        ray: &mut Ray,
+        world: &Arc<dyn Hittable>,
+        hit: &mut HitRecord,
+        throughput: &mut Vector3<f64>,
+    ) -> bool {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..MAX_MEDIUM_STEPS {
+            if !world.hit(ray, 0.0001, f64::MAX, hit) {
+                return false;
+            }
+
+            let medium = match ray.interior.current() {
+                Some(medium) => medium,
+                None => return true,
+            };
+
+            let length = ray.direction.magnitude();
+            let distance_to_hit = hit.t * length;
+            let extinction = medium.extinction();
+
+            if !medium.is_scattering() {
+                *throughput =
+                    throughput.component_mul(&(-extinction * distance_to_hit).map(f64::exp));
+                return true;
+            }
+
+            // Free flight distance sampled on one channel, weighted by the average over channels.
+            let channel = rng.gen_range(0..3);
+            let distance = -(1.0 - rng.gen::<f64>()).ln() / extinction[channel];
+
+            if distance >= distance_to_hit {
+                let transmittance = (-extinction * distance_to_hit).map(f64::exp);
+                *throughput = throughput.component_mul(&transmittance) / transmittance.mean();
+                return true;
+            }
+
+            let transmittance = (-extinction * distance).map(f64::exp);
+            let pdf = extinction.component_mul(&transmittance).mean();
+            *throughput =
+                throughput.component_mul(&medium.scattering.component_mul(&transmittance)) / pdf;
+
+            let direction = Vector3::new_random_in_unit_sphere().normalize();
+            *ray = ray.child(ray.at(distance / length), direction);
+            *hit = HitRecord::new();
         }
 
-        let scatter = &Self::ray_color(&scattered, world, background_color, depth - 1);
+        *throughput = Vector3::zeros();
+        false
+    }
+}
+
+fn power_heuristic(pdf: f64, other_pdf: f64) -> f64 {
+    let (squared, other_squared) = (pdf * pdf, other_pdf * other_pdf);
 
-        return emitted + attenuation.component_mul(scatter);
+    if squared + other_squared > 0.0 {
+        squared / (squared + other_squared)
+    } else {
+        0.0
     }
 }
@@ -1,5 +1,6 @@
 use crate::bvh::BVH;
 use crate::camera::Camera;
+use crate::environment::{Environment, HdriEnvironment, SkyEnvironment};
 use crate::hittable::{Hittable, HittableList};
 use crate::material::*;
 use crate::shape::*;
@@ -15,11 +16,35 @@ use nalgebra::Vector3;
 pub struct Scene {
     pub camera: Camera,
     pub background_color: Vector3<f64>,
+    // Replaces the background color when set, and lights the scene.
+    pub environment: Option<Arc<dyn Environment>>,
     pub world: Arc<dyn Hittable>,
+    // Images loaded for the scene, kept to report their memory.
+    pub textures: TextureCache,
 }
 
 impl Scene {
+    // Scene built by the function of the same name.
+    pub fn named(name: &str) -> Option<Scene> {
+        match name {
+            "random" => Some(Scene::random()),
+            "cornell_box" => Some(Scene::cornell_box()),
+            "iki_box" => Some(Scene::iki_box()),
+            "tim_box" => Some(Scene::tim_box()),
+            "monkey" => Some(Scene::monkey()),
+            "hdri" => Some(Scene::hdri()),
+            "sky" => Some(Scene::sky()),
+            "glass" => Some(Scene::glass()),
+            "materials" => Some(Scene::materials()),
+            _ => None,
+        }
+    }
+
     pub fn random() -> Scene {
+        // Images are decoded as rendering reaches them.
+        let mut textures = TextureCache::new();
+        textures.set_lazy(true);
+
         // Camera
         let look_from = Vector3::new(13.0, 2.0, 3.0);
         let look_at = Vector3::new(0.0, 0.0, 0.0);
@@ -92,8 +117,7 @@ impl Scene {
             material_left,
         ));
 
-        let material_center =
-            LambertianMaterial::arc(ImageTexture::arc("resources/earth.jpg".to_string()));
+        let material_center = LambertianMaterial::arc(textures.arc("resources/earth.jpg"));
         world.add(Sphere::arc(
             Vector3::new(0.0, 1.0, 0.0),
             1.0,
@@ -108,14 +132,15 @@ impl Scene {
             material_right,
         ));
 
-        let diffuse_light =
-            EmissiveMaterial::arc(ImageTexture::arc("resources/earth.jpg".to_string()));
+        let diffuse_light = EmissiveMaterial::arc(textures.arc("resources/earth.jpg"));
         world.add(Sphere::arc(Vector3::new(8.0, 1.0, 0.0), 1.0, diffuse_light));
 
         Scene {
             camera: camera,
             background_color: Vector3::new(0.9, 0.9, 0.9),
+            environment: None,
             world: BVH::arc(&mut world.objects),
+            textures,
         }
     }
 
@@ -137,6 +162,7 @@ impl Scene {
         );
 
         let mut world = HittableList::new();
+        let textures = TextureCache::new();
 
         let red_material =
             LambertianMaterial::arc(SolidColorTexture::arc(Vector3::new(0.65, 0.05, 0.05)));
@@ -224,7 +250,9 @@ impl Scene {
         Scene {
             camera: camera,
             background_color: Vector3::new(0.9, 0.9, 0.9),
+            environment: None,
             world: BVH::arc(&mut world.objects),
+            textures,
         }
     }
 
@@ -246,6 +274,7 @@ impl Scene {
         );
 
         let mut world = HittableList::new();
+        let textures = TextureCache::new();
 
         let red_material =
             LambertianMaterial::arc(SolidColorTexture::arc(Vector3::new(0.2, 0.1, 0.0)));
@@ -329,7 +358,9 @@ impl Scene {
         Scene {
             camera: camera,
             background_color: Vector3::new(0.0, 0.0, 0.0),
+            environment: None,
             world: BVH::arc(&mut world.objects),
+            textures,
         }
     }
 
@@ -351,6 +382,7 @@ impl Scene {
         );
 
         let mut world = HittableList::new();
+        let textures = TextureCache::new();
 
         let right_material =
             LambertianMaterial::arc(SolidColorTexture::arc(Vector3::new(1.0, 0.18, 0.62)));
@@ -434,7 +466,9 @@ impl Scene {
         Scene {
             camera: camera,
             background_color: Vector3::new(0.0, 0.0, 0.0),
+            environment: None,
             world: BVH::arc(&mut world.objects),
+            textures,
         }
     }
 
@@ -456,6 +490,7 @@ impl Scene {
         );
 
         let mut world = HittableList::new();
+        let textures = TextureCache::new();
 
         let right_material =
             LambertianMaterial::arc(SolidColorTexture::arc(Vector3::new(1.0, 0.18, 0.62)));
@@ -529,7 +564,494 @@ impl Scene {
         Scene {
             camera: camera,
             background_color: Vector3::new(1.0, 1.0, 1.0),
+            environment: None,
             world: Arc::new(world),
+            textures,
+        }
+    }
+
+    pub fn hdri() -> Scene {
+        // Studio lights to the left of the camera, turned by the rotation in degrees.
+        let environment = HdriEnvironment::arc(String::from("resources/studio.hdr"), 120.0, 1.5);
+
+        Scene::spheres_on_ground(environment)
+    }
+
+    pub fn sky() -> Scene {
+        // Late afternoon sun behind the spheres on the right, over a grey ground.
+        let environment = SkyEnvironment::arc(20.0, -60.0, 3.0, Vector3::new(0.3, 0.3, 0.3));
+
+        Scene::spheres_on_ground(environment)
+    }
+
+    pub fn glass() -> Scene {
+        // Camera
+        let look_from = Vector3::new(0.0, 3.0, 13.0);
+        let look_at = Vector3::new(0.0, 0.8, -1.0);
+        let v_up = Vector3::new(0.0, 1.0, 0.0);
+        let focus_distance = 14.0;
+
+        let camera = Camera::new(
+            look_from,
+            look_at,
+            v_up,
+            35.0,
+            ASPECT_RATIO,
+            0.0,
+            focus_distance,
+        );
+
+        let mut world = HittableList::new();
+        let textures = TextureCache::new();
+
+        let ground_material = LambertianMaterial::arc(CheckerTexture::arc(
+            SolidColorTexture::arc(Vector3::new(0.2, 0.2, 0.2)),
+            SolidColorTexture::arc(Vector3::new(0.8, 0.8, 0.8)),
+        ));
+        world.add(Sphere::arc(
+            Vector3::new(0.0, -1000.0, 0.0),
+            1000.0,
+            ground_material,
+        ));
+
+        // Front row: crown glass, diamond, tinted flint, wax and a soap bubble.
+        let crown_material = DielectricMaterial::arc_dispersive(RefractiveIndex::BK7);
+        let diamond_material = DielectricMaterial::arc_dispersive(RefractiveIndex::DIAMOND);
+
+        let mut flint_material = DielectricMaterial::new(RefractiveIndex::abbe(1.62, 36.4));
+        flint_material.set_color_at_distance(Vector3::new(0.55, 0.8, 0.95), 1.0);
+
+        let wax_material = DielectricMaterial::arc_subsurface(
+            1.45,
+            Vector3::new(0.05, 0.08, 0.15),
+            Vector3::new(0.99, 0.95, 0.85),
+        );
+
+        // Film thickness in nanometers, swirling between 250 and 700.
+        let bubble_thickness = RampTexture::arc(
+            NoiseTexture::arc(NoiseKind::Fbm, 2.0, 4, 2.0, 0.5, ColorRamp::grayscale()),
+            ColorRamp::new(vec![
+                (0.0, Vector3::repeat(250.0)),
+                (1.0, Vector3::repeat(700.0)),
+            ]),
+        );
+        let mut bubble_material = DielectricMaterial::new(RefractiveIndex::Constant(1.0));
+        bubble_material.set_thin_film(ThinFilm::new(bubble_thickness, 1.33));
+
+        let front_materials: [Arc<dyn Material>; 5] = [
+            crown_material,
+            diamond_material,
+            Arc::new(flint_material),
+            wax_material,
+            Arc::new(bubble_material),
+        ];
+
+        for (i, material) in front_materials.into_iter().enumerate() {
+            world.add(Sphere::arc(
+                Vector3::new(-3.6 + 1.8 * i as f64, 0.8, 0.0),
+                0.8,
+                material,
+            ));
+        }
+
+        // Back row: a glass ball filled with water, where the water takes over inside the glass,
+        // and a titanium ball tinted by its oxide layer.
+        let mut glass_material = DielectricMaterial::new(RefractiveIndex::Constant(1.5));
+        glass_material.set_priority(1);
+        world.add(Sphere::arc(
+            Vector3::new(-1.2, 1.0, -2.5),
+            1.0,
+            Arc::new(glass_material),
+        ));
+
+        let mut water_material = DielectricMaterial::new(RefractiveIndex::Constant(1.33));
+        water_material.set_absorption(Vector3::new(0.5, 0.15, 0.1));
+        water_material.set_priority(2);
+        world.add(Sphere::arc(
+            Vector3::new(-1.2, 1.0, -2.5),
+            0.9,
+            Arc::new(water_material),
+        ));
+
+        let mut titanium_material =
+            MetalMaterial::new(SolidColorTexture::arc(Vector3::new(0.55, 0.5, 0.45)), 0.0);
+        titanium_material.set_thin_film(ThinFilm::new(ValueTexture::arc(320.0), 2.4));
+        world.add(Sphere::arc(
+            Vector3::new(1.2, 1.0, -2.5),
+            1.0,
+            Arc::new(titanium_material),
+        ));
+
+        // Window pane to the left, in front of the back row.
+        world.add(RectangleXY::arc(
+            (-4.5, -2.5),
+            (0.0, 2.5),
+            -1.5,
+            ThinDielectricMaterial::arc(1.5),
+        ));
+
+        Scene {
+            camera: camera,
+            background_color: Vector3::new(0.0, 0.0, 0.0),
+            environment: Some(SkyEnvironment::arc(
+                35.0,
+                30.0,
+                3.0,
+                Vector3::new(0.3, 0.3, 0.3),
+            )),
+            world: BVH::arc(&mut world.objects),
+            textures,
+        }
+    }
+
+    pub fn materials() -> Scene {
+        // Camera
+        let look_from = Vector3::new(0.0, 4.0, 9.5);
+        let look_at = Vector3::new(0.0, 0.4, -1.4);
+        let v_up = Vector3::new(0.0, 1.0, 0.0);
+        let focus_distance = 11.0;
+
+        let camera = Camera::new(
+            look_from,
+            look_at,
+            v_up,
+            35.0,
+            ASPECT_RATIO,
+            0.0,
+            focus_distance,
+        );
+
+        let mut world = HittableList::new();
+        let textures = TextureCache::new();
+
+        let white = || SolidColorTexture::arc(Vector3::new(0.8, 0.8, 0.8));
+
+        // Mirrored earth tiles on the floor, sharpened by bicubic and anisotropic filtering at
+        // grazing angles.
+        let mut floor_image = textures.get("resources/earth.jpg", None);
+        floor_image.set_filter(ImageFilter::Bicubic);
+        floor_image.set_mip_filter(MipFilter::Anisotropic);
+        floor_image.set_wrap(WrapMode::Mirror, WrapMode::Clamp);
+        let floor_texture = HsvTexture::arc(
+            TransformTexture::arc_tiled(Arc::new(floor_image), 4.0, 1.0),
+            0.0,
+            0.3,
+            0.6,
+        );
+        world.add(RectangleXZ::arc(
+            (-6.0, 6.0),
+            (-6.0, 3.0),
+            0.0,
+            LambertianMaterial::arc(floor_texture),
+        ));
+
+        // Postcard of the earth on the back wall, cut out around the image by a transparent
+        // border.
+        let mut postcard_image = textures.get("resources/earth.jpg", None);
+        postcard_image.set_filter(ImageFilter::Nearest);
+        postcard_image.set_mip_filter(MipFilter::None);
+        postcard_image.set_wrap(WrapMode::Border, WrapMode::Border);
+        postcard_image.set_border(Vector3::zeros(), 0.0);
+        let mut postcard_texture = TransformTexture::new(Arc::new(postcard_image));
+        postcard_texture.set_uv_scale(1.2, 1.2);
+        postcard_texture.set_uv_offset(-0.1, -0.1);
+        let postcard_texture: Arc<dyn Texture> = Arc::new(postcard_texture);
+        world.add(RectangleXY::arc(
+            (-2.0, 2.0),
+            (0.5, 2.5),
+            -4.5,
+            CutoutMaterial::arc(
+                LambertianMaterial::arc(Arc::clone(&postcard_texture)),
+                postcard_texture,
+            ),
+        ));
+
+        world.add(RectangleXY::arc(
+            (-6.0, 6.0),
+            (0.0, 4.0),
+            -5.5,
+            LambertianMaterial::arc(SolidColorTexture::arc(Vector3::new(0.4, 0.4, 0.4))),
+        ));
+
+        // Light above the spheres, shining down only and hidden from the camera.
+        let mut light_material =
+            EmissiveMaterial::new(SolidColorTexture::arc(Vector3::new(1.0, 0.95, 0.9)));
+        light_material.set_scale(6.0);
+        light_material.set_one_sided(true);
+        light_material.set_visible_to_camera(false);
+        world.add(FlipFace::arc(RectangleXZ::arc(
+            (-2.0, 2.0),
+            (-3.0, 0.0),
+            5.0,
+            Arc::new(light_material),
+        )));
+
+        // Front row: rough and layered diffuse surfaces.
+        let clay_material = OrenNayarMaterial::arc(
+            SolidColorTexture::arc(Vector3::new(0.75, 0.45, 0.3)),
+            ValueTexture::arc(0.6),
+        );
+
+        let velvet_material = SheenMaterial::arc_layered(
+            SolidColorTexture::arc(Vector3::new(1.0, 0.6, 0.8)),
+            ValueTexture::arc(0.4),
+            LambertianMaterial::arc(SolidColorTexture::arc(Vector3::new(0.4, 0.05, 0.15))),
+        );
+
+        let leaf_material = TranslucentMaterial::arc(
+            SolidColorTexture::arc(Vector3::new(0.3, 0.6, 0.1)),
+            SolidColorTexture::arc(Vector3::new(0.5, 0.7, 0.3)),
+        );
+
+        // Dust settling on a cloth where a noise pattern is bright enough.
+        let dust_factor = ClampTexture::arc(
+            MathTexture::arc(
+                MathOperation::Subtract,
+                NoiseTexture::arc(NoiseKind::Perlin, 4.0, 1, 2.0, 0.5, ColorRamp::grayscale()),
+                ValueTexture::arc(0.3),
+            ),
+            0.0,
+            1.0,
+        );
+        let dusty_material = MixMaterial::arc(
+            SheenMaterial::arc(
+                SolidColorTexture::arc(Vector3::new(0.2, 0.3, 0.7)),
+                ValueTexture::arc(0.6),
+            ),
+            LambertianMaterial::arc(white()),
+            dust_factor,
+        );
+
+        // Middle row: metals and coatings.
+        let brushed_material = AnisotropicMetalMaterial::arc(
+            SolidColorTexture::arc(Vector3::new(0.9, 0.9, 0.92)),
+            ValueTexture::arc(0.05),
+            ValueTexture::arc(0.4),
+            30.0,
+        );
+
+        let paint_material = CoatedMaterial::arc(
+            LambertianMaterial::arc(SolidColorTexture::arc(Vector3::new(0.7, 0.05, 0.05))),
+            1.5,
+            0.1,
+            Vector3::new(0.95, 0.95, 0.9),
+        );
+
+        let mut marble = NoiseTexture::new(
+            NoiseKind::Marble,
+            1.5,
+            6,
+            2.0,
+            0.5,
+            ColorRamp::new(vec![
+                (0.0, Vector3::new(0.9, 0.9, 0.88)),
+                (0.7, Vector3::new(0.75, 0.75, 0.72)),
+                (1.0, Vector3::new(0.25, 0.25, 0.3)),
+            ]),
+        );
+        marble.set_space(TextureSpace::World);
+        let marble: Arc<dyn Texture> = Arc::new(marble);
+        let gilded_material = MixMaterial::arc(
+            LambertianMaterial::arc(Arc::clone(&marble)),
+            MetalMaterial::arc(SolidColorTexture::arc(Vector3::new(1.0, 0.8, 0.3)), 0.1),
+            InvertTexture::arc(marble),
+        );
+
+        let hammered_material = BumpMapMaterial::arc(
+            MetalMaterial::arc(SolidColorTexture::arc(Vector3::new(0.95, 0.65, 0.5)), 0.05),
+            NoiseTexture::arc(
+                NoiseKind::Turbulence,
+                6.0,
+                3,
+                2.0,
+                0.5,
+                ColorRamp::grayscale(),
+            ),
+            0.3,
+        );
+
+        // Back row: textures driving the color and the shading normal.
+        let tiles_material = NormalMapMaterial::arc(
+            LambertianMaterial::arc(SolidColorTexture::arc(Vector3::new(0.6, 0.65, 0.7))),
+            TransformTexture::arc_tiled(
+                textures.arc_linear("resources/tiles_normal.png"),
+                2.0,
+                1.0,
+            ),
+            1.0,
+        );
+
+        let mut wood = NoiseTexture::new(
+            NoiseKind::Wood,
+            2.0,
+            2,
+            2.0,
+            0.5,
+            ColorRamp::new(vec![
+                (0.0, Vector3::new(0.45, 0.25, 0.1)),
+                (1.0, Vector3::new(0.7, 0.45, 0.2)),
+            ]),
+        );
+        wood.set_space(TextureSpace::Object);
+        let mut wood = TransformTexture::new(Arc::new(wood));
+        wood.set_point_rotation(Vector3::new(1.0, 0.0, 0.0), 90.0);
+        wood.set_point_scale(Vector3::new(1.0, 1.0, 0.3));
+        wood.set_point_offset(Vector3::new(0.3, 0.0, 0.0));
+        let wood_material = LambertianMaterial::arc(Arc::new(wood));
+
+        // Rotated stripes of two colors, brightened where a second pattern adds to them.
+        let mut stripes = TransformTexture::new(CheckerTexture::arc_space(
+            white(),
+            SolidColorTexture::arc(Vector3::new(0.1, 0.3, 0.6)),
+            TextureSpace::Uv,
+        ));
+        stripes.set_uv_rotation(30.0);
+        stripes.set_uv_scale(0.05, 1.0);
+        let stripes_texture = MathTexture::arc_add(
+            MathTexture::arc_multiply(Arc::new(stripes), ValueTexture::arc(0.8)),
+            MixTexture::arc(
+                SolidColorTexture::arc(Vector3::zeros()),
+                SolidColorTexture::arc(Vector3::new(0.2, 0.15, 0.0)),
+                NoiseTexture::arc(NoiseKind::Perlin, 3.0, 1, 2.0, 0.5, ColorRamp::grayscale()),
+            ),
+        );
+        let stripes_material = LambertianMaterial::arc(stripes_texture);
+
+        // Glowing veins over dark stone.
+        let lava_material = EmissiveMaterial::arc_layered(
+            RampTexture::arc(
+                NoiseTexture::arc(
+                    NoiseKind::Turbulence,
+                    3.0,
+                    4,
+                    2.0,
+                    0.5,
+                    ColorRamp::grayscale(),
+                ),
+                ColorRamp::new(vec![
+                    (0.0, Vector3::new(4.0, 1.0, 0.1)),
+                    (0.15, Vector3::zeros()),
+                ]),
+            ),
+            LambertianMaterial::arc(SolidColorTexture::arc(Vector3::new(0.1, 0.1, 0.1))),
+        );
+
+        let rows: [[Arc<dyn Material>; 4]; 3] = [
+            [
+                clay_material,
+                velvet_material,
+                leaf_material,
+                dusty_material,
+            ],
+            [
+                brushed_material,
+                paint_material,
+                gilded_material,
+                hammered_material,
+            ],
+            [
+                tiles_material,
+                wood_material,
+                stripes_material,
+                lava_material,
+            ],
+        ];
+
+        for (row, materials) in rows.into_iter().enumerate() {
+            for (column, material) in materials.into_iter().enumerate() {
+                world.add(Sphere::arc(
+                    Vector3::new(-2.1 + 1.4 * column as f64, 0.55, -1.4 * row as f64),
+                    0.55,
+                    material,
+                ));
+            }
+        }
+
+        // Pedestal for the glass cube behind the spheres on the right.
+        world.add(Cube::arc(
+            Vector3::new(2.6, 0.0, -3.9),
+            Vector3::new(3.4, 0.4, -3.1),
+            OrenNayarMaterial::arc(white(), ValueTexture::arc(0.3)),
+        ));
+        world.add(Cube::arc(
+            Vector3::new(2.7, 0.4, -3.8),
+            Vector3::new(3.3, 1.0, -3.2),
+            DielectricMaterial::arc(1.5),
+        ));
+
+        Scene {
+            camera: camera,
+            background_color: Vector3::new(0.0, 0.0, 0.0),
+            environment: Some(HdriEnvironment::arc(
+                String::from("resources/studio.hdr"),
+                0.0,
+                0.5,
+            )),
+            world: BVH::arc(&mut world.objects),
+            textures,
+        }
+    }
+
+    // Diffuse, metal and glass spheres on a checkered ground, lit only by the environment.
+    fn spheres_on_ground(environment: Arc<dyn Environment>) -> Scene {
+        // Camera
+        let look_from = Vector3::new(0.0, 2.0, 9.0);
+        let look_at = Vector3::new(0.0, 0.8, 0.0);
+        let v_up = Vector3::new(0.0, 1.0, 0.0);
+        let focus_distance = 9.0;
+
+        let camera = Camera::new(
+            look_from,
+            look_at,
+            v_up,
+            35.0,
+            ASPECT_RATIO,
+            0.0,
+            focus_distance,
+        );
+
+        let mut world = HittableList::new();
+        let textures = TextureCache::new();
+
+        let ground_material = LambertianMaterial::arc(CheckerTexture::arc(
+            SolidColorTexture::arc(Vector3::new(0.2, 0.2, 0.2)),
+            SolidColorTexture::arc(Vector3::new(0.8, 0.8, 0.8)),
+        ));
+        world.add(Sphere::arc(
+            Vector3::new(0.0, -1000.0, 0.0),
+            1000.0,
+            ground_material,
+        ));
+
+        let left_material =
+            LambertianMaterial::arc(SolidColorTexture::arc(Vector3::new(0.7, 0.3, 0.2)));
+        world.add(Sphere::arc(
+            Vector3::new(-2.2, 1.0, 0.0),
+            1.0,
+            left_material,
+        ));
+
+        let center_material =
+            MetalMaterial::arc(SolidColorTexture::arc(Vector3::new(0.9, 0.9, 0.9)), 0.05);
+        world.add(Sphere::arc(
+            Vector3::new(0.0, 1.0, 0.0),
+            1.0,
+            center_material,
+        ));
+
+        let right_material = DielectricMaterial::arc(1.5);
+        world.add(Sphere::arc(
+            Vector3::new(2.2, 1.0, 0.0),
+            1.0,
+            right_material,
+        ));
+
+        Scene {
+            camera: camera,
+            background_color: Vector3::new(0.0, 0.0, 0.0),
+            environment: Some(environment),
+            world: BVH::arc(&mut world.objects),
+            textures,
         }
     }
 }
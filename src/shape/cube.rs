@@ -8,6 +8,7 @@ use crate::hittable::Hittable;
 use crate::hittable::HittableList;
 use crate::material::Material;
 use crate::ray::Ray;
+use crate::transform::FlipFace;
 
 use std::sync::Arc;
 
@@ -26,6 +27,8 @@ impl Cube {
         maximum: Vector3<f64>,
         material: Arc<dyn Material>,
     ) -> Arc<Cube> {
+        // The rectangles face along their positive axis, so the sides at the minimum are flipped
+        // to face outward.
         let mut sides = HittableList::new();
 
         sides.add(RectangleXY::arc(
@@ -34,12 +37,12 @@ impl Cube {
             maximum.z,
             Arc::clone(&material),
         ));
-        sides.add(RectangleXY::arc(
+        sides.add(FlipFace::arc(RectangleXY::arc(
             (minimum.x, maximum.x),
             (minimum.y, maximum.y),
             minimum.z,
             Arc::clone(&material),
-        ));
+        )));
 
         sides.add(RectangleXZ::arc(
             (minimum.x, maximum.x),
@@ -47,12 +50,12 @@ impl Cube {
             maximum.y,
             Arc::clone(&material),
         ));
-        sides.add(RectangleXZ::arc(
+        sides.add(FlipFace::arc(RectangleXZ::arc(
             (minimum.x, maximum.x),
             (minimum.z, maximum.z),
             minimum.y,
             Arc::clone(&material),
-        ));
+        )));
 
         sides.add(RectangleYZ::arc(
             (minimum.y, maximum.y),
@@ -60,12 +63,12 @@ impl Cube {
             maximum.x,
             Arc::clone(&material),
         ));
-        sides.add(RectangleYZ::arc(
+        sides.add(FlipFace::arc(RectangleYZ::arc(
             (minimum.y, maximum.y),
             (minimum.z, maximum.z),
             minimum.x,
             Arc::clone(&material),
-        ));
+        )));
 
         Arc::new(Cube {
             minimum,
@@ -86,3 +89,36 @@ impl Hittable for Cube {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::material::LambertianMaterial;
+    use crate::texture::SolidColorTexture;
+
+    // Whether rays along each axis, in both directions, see the outside of the unit cube when they
+    // come from outside and the inside when they start at its center.
+    #[test]
+    fn every_face_points_outward() {
+        let material = LambertianMaterial::arc(SolidColorTexture::arc(Vector3::repeat(1.0)));
+        let cube = Cube::arc(Vector3::repeat(-1.0), Vector3::repeat(1.0), material);
+
+        for axis in 0..3 {
+            for sign in [-1.0, 1.0] {
+                let mut direction = Vector3::zeros();
+                direction[axis] = sign;
+
+                let mut hit = HitRecord::new();
+                let entering = Ray::new(-5.0 * direction, direction);
+                assert!(cube.hit(&entering, 0.0001, f64::MAX, &mut hit));
+                assert!(hit.front_face);
+
+                let mut hit = HitRecord::new();
+                let leaving = Ray::new(Vector3::zeros(), direction);
+                assert!(cube.hit(&leaving, 0.0001, f64::MAX, &mut hit));
+                assert!(!hit.front_face);
+            }
+        }
+    }
+}
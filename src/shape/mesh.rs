@@ -53,11 +53,19 @@ impl Mesh {
                 .z
                 .max(vertices[0].z.max(vertices[1].z.max(vertices[2].z)));
 
-            faces.add(Triangle::arc_normal(
-                vertices,
-                normals,
-                Arc::clone(&material),
-            ));
+            match (f[0].2, f[1].2, f[2].2) {
+                (Some(uv0), Some(uv1), Some(uv2)) => faces.add(Triangle::arc_uv(
+                    vertices,
+                    normals,
+                    [uv0, uv1, uv2].to_vec(),
+                    Arc::clone(&material),
+                )),
+                _ => faces.add(Triangle::arc_normal(
+                    vertices,
+                    normals,
+                    Arc::clone(&material),
+                )),
+            }
         }
 
         let faces = BVH::arc(&mut faces.objects);
@@ -3,10 +3,11 @@ use crate::hittable::HitRecord;
 use crate::hittable::Hittable;
 use crate::material::Material;
 use crate::ray::Ray;
+use crate::texture::TexturePoint;
 
 use std::sync::Arc;
 
-use nalgebra::Vector3;
+use nalgebra::{Rotation3, Vector3};
 
 pub struct RectangleXZ {
     x: (f64, f64),
@@ -38,12 +39,27 @@ impl Hittable for RectangleXZ {
             return false;
         }
 
+        let uv = (
+            (point.x - self.x.0) / (self.x.1 - self.x.0),
+            (point.z - self.z.0) / (self.z.1 - self.z.0),
+        );
+
+        if !self
+            .material
+            .alpha_test(uv, &TexturePoint::new(ray.world_at(t), point))
+        {
+            return false;
+        }
+
         hit.t = t;
         hit.point = point;
+        hit.object_point = point;
+        hit.object_rotation = Rotation3::identity();
         hit.normal = Vector3::new(0.0, 1.0, 0.0);
+        hit.tangent = Vector3::new(self.x.1 - self.x.0, 0.0, 0.0);
+        hit.bitangent = Vector3::new(0.0, 0.0, self.z.1 - self.z.0);
         hit.material = Arc::clone(&self.material);
-        hit.uv.0 = (point.x - self.x.0) / (self.x.1 - self.x.0);
-        hit.uv.1 = (point.z - self.z.0) / (self.z.1 - self.z.0);
+        hit.uv = uv;
 
         hit.set_face_normal(ray.direction, hit.normal);
 
@@ -3,11 +3,12 @@ use crate::hittable::HitRecord;
 use crate::hittable::Hittable;
 use crate::material::Material;
 use crate::ray::Ray;
+use crate::texture::TexturePoint;
 
 use std::f64::consts::PI;
 use std::sync::Arc;
 
-use nalgebra::Vector3;
+use nalgebra::{Rotation3, Vector3};
 
 pub struct Sphere {
     center: Vector3<f64>,
@@ -51,23 +52,49 @@ impl Hittable for Sphere {
 
         let sqrt_d = discriminant.sqrt();
 
-        let mut root = (-half_b - sqrt_d) / a;
-        if root < t_min || root > t_max {
-            root = (-half_b + sqrt_d) / a;
+        // The far root is still a candidate when the near one is cut out by the material.
+        for root in [(-half_b - sqrt_d) / a, (-half_b + sqrt_d) / a] {
             if root < t_min || root > t_max {
-                return false;
+                continue;
             }
-        }
 
-        hit.t = root;
-        hit.point = ray.at(root);
-        hit.normal = (hit.point - self.center) / self.radius;
-        hit.material = Arc::clone(&self.material);
-        hit.uv = self.get_uv(hit.normal);
+            let point = ray.at(root);
+            let normal = (point - self.center) / self.radius;
+            let uv = self.get_uv(normal);
+
+            if !self
+                .material
+                .alpha_test(uv, &TexturePoint::new(ray.world_at(root), point))
+            {
+                continue;
+            }
 
-        hit.set_face_normal(ray.direction, hit.normal);
+            hit.t = root;
+            hit.point = point;
+            hit.object_point = point;
+            hit.object_rotation = Rotation3::identity();
+            hit.normal = normal;
+            // Derivatives of the spherical mapping, which vanish at the poles.
+            let ring_radius = (normal.x * normal.x + normal.z * normal.z)
+                .sqrt()
+                .max(1e-12);
+            hit.tangent = 2.0 * PI * self.radius * Vector3::new(normal.z, 0.0, -normal.x);
+            hit.bitangent = PI
+                * self.radius
+                * Vector3::new(
+                    -normal.x * normal.y / ring_radius,
+                    ring_radius,
+                    -normal.z * normal.y / ring_radius,
+                );
+            hit.material = Arc::clone(&self.material);
+            hit.uv = uv;
+
+            hit.set_face_normal(ray.direction, hit.normal);
+
+            return true;
+        }
 
-        return true;
+        false
     }
 
     fn bounding_box(&self, output_box: &mut AABB) -> bool {
@@ -3,22 +3,29 @@ use crate::hittable::HitRecord;
 use crate::hittable::Hittable;
 use crate::material::Material;
 use crate::ray::Ray;
+use crate::texture::TexturePoint;
 
 use std::sync::Arc;
 
-use nalgebra::Vector3;
+use nalgebra::{Rotation3, Vector3};
 
 pub struct Triangle {
     vertices: Vec<Vector3<f64>>,
     normals: Vec<Vector3<f64>>,
+    uvs: Vec<(f64, f64)>,
+    // Outward facing normal, which tells entering and leaving rays apart.
+    normal: Vector3<f64>,
+    tangent: Vector3<f64>,
+    bitangent: Vector3<f64>,
     indices: Vec<usize>,
     material: Arc<dyn Material>,
 }
 
 impl Triangle {
-    pub fn arc_normal(
+    pub fn arc_uv(
         vertices: Vec<Vector3<f64>>,
         normals: Vec<Vector3<f64>>,
+        uvs: Vec<(f64, f64)>,
         material: Arc<dyn Material>,
     ) -> Arc<Triangle> {
         let determinant = Vector3::dot(&-vertices[0], &normals[0]);
@@ -35,14 +42,36 @@ impl Triangle {
             indices = [2, 0, 1].to_vec();
         }
 
+        let (tangent, bitangent) = Triangle::tangents(&vertices, &uvs);
+
+        // Counter-clockwise winding faces outward, unless the vertex normals point the other way.
+        let mut normal =
+            Vector3::cross(&(vertices[1] - vertices[0]), &(vertices[2] - vertices[0])).normalize();
+        if Vector3::dot(&normal, &(normals[0] + normals[1] + normals[2])) < 0.0 {
+            normal = -normal;
+        }
+
         Arc::new(Triangle {
             vertices,
             normals,
+            uvs,
+            normal,
+            tangent,
+            bitangent,
             indices,
             material,
         })
     }
 
+    pub fn arc_normal(
+        vertices: Vec<Vector3<f64>>,
+        normals: Vec<Vector3<f64>>,
+        material: Arc<dyn Material>,
+    ) -> Arc<Triangle> {
+        let uvs = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)].to_vec();
+        Triangle::arc_uv(vertices, normals, uvs, material)
+    }
+
     pub fn arc(vertices: Vec<Vector3<f64>>, material: Arc<dyn Material>) -> Arc<Triangle> {
         let normal =
             Vector3::cross(&(vertices[1] - vertices[0]), &(vertices[2] - vertices[0])).normalize();
@@ -50,6 +79,26 @@ impl Triangle {
         let normals = [normal, normal, normal].to_vec();
         Triangle::arc_normal(vertices, normals, material)
     }
+
+    // Derivatives of the point with respect to u and v across the triangle.
+    fn tangents(vertices: &[Vector3<f64>], uvs: &[(f64, f64)]) -> (Vector3<f64>, Vector3<f64>) {
+        let ab = vertices[1] - vertices[0];
+        let ac = vertices[2] - vertices[0];
+
+        let (du_ab, dv_ab) = (uvs[1].0 - uvs[0].0, uvs[1].1 - uvs[0].1);
+        let (du_ac, dv_ac) = (uvs[2].0 - uvs[0].0, uvs[2].1 - uvs[0].1);
+
+        let determinant = du_ab * dv_ac - du_ac * dv_ab;
+        if determinant.abs() < 1e-12 {
+            let normal = Vector3::cross(&ab, &ac);
+            return (ab.normalize(), Vector3::cross(&normal, &ab).normalize());
+        }
+
+        let tangent = (ab * dv_ac - ac * dv_ab) / determinant;
+        let bitangent = (ac * du_ab - ab * du_ac) / determinant;
+
+        (tangent, bitangent)
+    }
 }
 
 impl Hittable for Triangle {
@@ -82,7 +131,6 @@ impl Hittable for Triangle {
         let mut v;
         let mut w;
         let toi;
-        let normal;
 
         if t < 0.0 {
             v = -ac.dot(&e);
@@ -99,7 +147,6 @@ impl Hittable for Triangle {
 
             let invd = 1.0 / d;
             toi = -t * invd;
-            normal = -n.normalize();
             v = v * invd;
             w = w * invd;
         } else {
@@ -117,15 +164,33 @@ impl Hittable for Triangle {
 
             let invd = 1.0 / d;
             toi = t * invd;
-            normal = n.normalize();
             v = v * invd;
             w = w * invd;
         }
 
+        let point = ray.at(toi);
+        let u = 1.0 - v - w;
+        let uv = (
+            u * self.uvs[0].0 + v * self.uvs[1].0 + w * self.uvs[2].0,
+            u * self.uvs[0].1 + v * self.uvs[1].1 + w * self.uvs[2].1,
+        );
+
+        if !self
+            .material
+            .alpha_test(uv, &TexturePoint::new(ray.world_at(toi), point))
+        {
+            return false;
+        }
+
         hit.t = toi;
-        hit.point = ray.at(toi);
-        hit.normal = normal;
+        hit.point = point;
+        hit.object_point = point;
+        hit.object_rotation = Rotation3::identity();
+        hit.normal = self.normal;
+        hit.tangent = self.tangent;
+        hit.bitangent = self.bitangent;
         hit.material = Arc::clone(&self.material);
+        hit.uv = uv;
 
         hit.set_face_normal(ray.direction, hit.normal);
 
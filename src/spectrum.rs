@@ -0,0 +1,66 @@
+use std::sync::OnceLock;
+
+use nalgebra::Vector3;
+use rand::Rng;
+
+pub const WAVELENGTH_MIN: f64 = 380.0;
+pub const WAVELENGTH_MAX: f64 = 780.0;
+
+// Sodium D line, the wavelength at which a single refractive index is usually quoted.
+pub const WAVELENGTH_D: f64 = 587.56;
+
+pub fn sample_wavelength() -> f64 {
+    rand::thread_rng().gen_range(WAVELENGTH_MIN..WAVELENGTH_MAX)
+}
+
+// RGB weight of a single wavelength, normalized so that averaging it over uniformly sampled
+// wavelengths gives white. This lets a path carry one wavelength inside the RGB renderer.
+pub fn wavelength_to_rgb(wavelength: f64) -> Vector3<f64> {
+    static MEAN: OnceLock<Vector3<f64>> = OnceLock::new();
+
+    let mean = MEAN.get_or_init(|| {
+        let steps = 4000;
+        let step = (WAVELENGTH_MAX - WAVELENGTH_MIN) / steps as f64;
+
+        (0..steps)
+            .map(|i| linear_rgb(WAVELENGTH_MIN + (i as f64 + 0.5) * step))
+            .sum::<Vector3<f64>>()
+            / steps as f64
+    });
+
+    linear_rgb(wavelength).component_div(mean)
+}
+
+fn linear_rgb(wavelength: f64) -> Vector3<f64> {
+    xyz_to_rgb(cie_xyz(wavelength))
+}
+
+// Linear sRGB of a CIE XYZ color, with out of gamut channels clipped to zero.
+pub fn xyz_to_rgb(xyz: Vector3<f64>) -> Vector3<f64> {
+    let r = 3.2406 * xyz.x - 1.5372 * xyz.y - 0.4986 * xyz.z;
+    let g = -0.9689 * xyz.x + 1.8758 * xyz.y + 0.0415 * xyz.z;
+    let b = 0.0557 * xyz.x - 0.2040 * xyz.y + 1.0570 * xyz.z;
+
+    Vector3::new(r.max(0.0), g.max(0.0), b.max(0.0))
+}
+
+// Multi-lobe fit of the CIE 1931 color matching functions (Wyman, Sloan and Shirley, 2013).
+fn cie_xyz(wavelength: f64) -> Vector3<f64> {
+    let lobe = |mu: f64, sigma_low: f64, sigma_high: f64| {
+        let sigma = if wavelength < mu {
+            sigma_low
+        } else {
+            sigma_high
+        };
+        let t = (wavelength - mu) / sigma;
+
+        (-0.5 * t * t).exp()
+    };
+
+    let x = 1.056 * lobe(599.8, 37.9, 31.0) + 0.362 * lobe(442.0, 16.0, 26.7)
+        - 0.065 * lobe(501.1, 20.4, 26.2);
+    let y = 0.821 * lobe(568.8, 46.9, 40.5) + 0.286 * lobe(530.9, 16.3, 31.1);
+    let z = 1.217 * lobe(437.0, 11.8, 36.0) + 0.681 * lobe(459.0, 26.0, 13.8);
+
+    Vector3::new(x, y, z)
+}
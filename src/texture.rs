@@ -1,3 +1,12 @@
+mod color_space;
+pub use color_space::ColorSpace;
+
+mod footprint;
+pub use footprint::Footprint;
+
+mod texture_point;
+pub use texture_point::{TexturePoint, TextureSpace};
+
 mod solid_color;
 pub use solid_color::SolidColorTexture;
 
@@ -5,10 +14,72 @@ mod checker;
 pub use checker::CheckerTexture;
 
 mod image;
-pub use self::image::ImageTexture;
+pub use self::image::{ImageFilter, ImageTexture, MipFilter, WrapMode};
+
+mod image_data;
+
+mod texture_cache;
+pub use texture_cache::TextureCache;
+
+mod perlin;
+
+mod color_ramp;
+pub use color_ramp::ColorRamp;
+
+mod noise;
+pub use noise::{NoiseKind, NoiseTexture};
+
+mod value;
+pub use value::ValueTexture;
+
+mod transform;
+pub use transform::TransformTexture;
+
+mod mix;
+pub use mix::MixTexture;
+
+mod math;
+pub use math::{MathOperation, MathTexture};
+
+mod invert;
+pub use invert::InvertTexture;
+
+mod clamp;
+pub use clamp::ClampTexture;
+
+mod ramp;
+pub use ramp::RampTexture;
+
+mod hsv;
+pub use hsv::HsvTexture;
+
+use crate::vector3_traits::Helpers;
 
 use nalgebra::Vector3;
 
+// Textures are either colors or scalars, and any of them can feed the other nodes. Scalar inputs
+// such as roughness, heights and mix factors read `get_value`, which falls back to the luminance
+// of the color. Images holding such data rather than colors have to be decoded with
+// `ColorSpace::Linear`, since 8-bit images are taken as sRGB by default.
+
 pub trait Texture: Sync + Send {
-    fn get_color(&self, uv: (f64, f64), point: &Vector3<f64>) -> Vector3<f64>;
+    fn get_color(&self, uv: (f64, f64), point: &TexturePoint) -> Vector3<f64>;
+
+    // Color averaged over the footprint of a pixel, for textures that can prefilter their detail.
+    fn get_color_filtered(
+        &self,
+        uv: (f64, f64),
+        point: &TexturePoint,
+        _footprint: &Footprint,
+    ) -> Vector3<f64> {
+        self.get_color(uv, point)
+    }
+
+    fn get_alpha(&self, _uv: (f64, f64), _point: &TexturePoint) -> f64 {
+        1.0
+    }
+
+    fn get_value(&self, uv: (f64, f64), point: &TexturePoint) -> f64 {
+        self.get_color(uv, point).luminance()
+    }
 }
@@ -1,4 +1,6 @@
+use super::Footprint;
 use super::Texture;
+use super::{TexturePoint, TextureSpace};
 
 use std::sync::Arc;
 
@@ -7,21 +9,44 @@ use nalgebra::Vector3;
 pub struct CheckerTexture {
     even: Arc<dyn Texture>,
     odd: Arc<dyn Texture>,
+    space: TextureSpace,
 }
 
 impl CheckerTexture {
     pub fn arc(even: Arc<dyn Texture>, odd: Arc<dyn Texture>) -> Arc<CheckerTexture> {
-        Arc::new(CheckerTexture { even, odd })
+        CheckerTexture::arc_space(even, odd, TextureSpace::Object)
+    }
+
+    pub fn arc_space(
+        even: Arc<dyn Texture>,
+        odd: Arc<dyn Texture>,
+        space: TextureSpace,
+    ) -> Arc<CheckerTexture> {
+        Arc::new(CheckerTexture { even, odd, space })
     }
 }
 
 impl Texture for CheckerTexture {
-    fn get_color(&self, uv: (f64, f64), point: &Vector3<f64>) -> Vector3<f64> {
-        let sines = f64::sin(10.0 * point.x) * f64::sin(10.0 * point.y) * f64::sin(10.0 * point.z);
+    fn get_color(&self, uv: (f64, f64), point: &TexturePoint) -> Vector3<f64> {
+        self.get_color_filtered(uv, point, &Footprint::default())
+    }
+
+    fn get_color_filtered(
+        &self,
+        uv: (f64, f64),
+        point: &TexturePoint,
+        footprint: &Footprint,
+    ) -> Vector3<f64> {
+        let p = self.space.coordinates(uv, point);
+        let sines = match self.space {
+            // Texture coordinates only span two axes.
+            TextureSpace::Uv => f64::sin(10.0 * p.x) * f64::sin(10.0 * p.y),
+            _ => f64::sin(10.0 * p.x) * f64::sin(10.0 * p.y) * f64::sin(10.0 * p.z),
+        };
         if sines > 0.0 {
-            return self.even.get_color(uv, point);
+            return self.even.get_color_filtered(uv, point, footprint);
         } else {
-            return self.odd.get_color(uv, point);
+            return self.odd.get_color_filtered(uv, point, footprint);
         }
     }
 }
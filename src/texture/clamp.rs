@@ -0,0 +1,40 @@
+use super::Footprint;
+use super::Texture;
+use super::TexturePoint;
+
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+
+pub struct ClampTexture {
+    texture: Arc<dyn Texture>,
+    min: f64,
+    max: f64,
+}
+
+impl ClampTexture {
+    pub fn arc(texture: Arc<dyn Texture>, min: f64, max: f64) -> Arc<ClampTexture> {
+        Arc::new(ClampTexture { texture, min, max })
+    }
+}
+
+impl Texture for ClampTexture {
+    fn get_color(&self, uv: (f64, f64), point: &TexturePoint) -> Vector3<f64> {
+        self.get_color_filtered(uv, point, &Footprint::default())
+    }
+
+    fn get_color_filtered(
+        &self,
+        uv: (f64, f64),
+        point: &TexturePoint,
+        footprint: &Footprint,
+    ) -> Vector3<f64> {
+        self.texture
+            .get_color_filtered(uv, point, footprint)
+            .map(|c| c.clamp(self.min, self.max))
+    }
+
+    fn get_value(&self, uv: (f64, f64), point: &TexturePoint) -> f64 {
+        self.texture.get_value(uv, point).clamp(self.min, self.max)
+    }
+}
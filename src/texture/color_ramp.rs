@@ -0,0 +1,49 @@
+use nalgebra::Vector3;
+
+// Maps a value in [0, 1] to a color by interpolating linearly between sorted color stops.
+#[derive(Clone)]
+pub struct ColorRamp {
+    stops: Vec<(f64, Vector3<f64>)>,
+}
+
+impl ColorRamp {
+    pub fn new(mut stops: Vec<(f64, Vector3<f64>)>) -> ColorRamp {
+        stops.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        ColorRamp { stops }
+    }
+
+    pub fn grayscale() -> ColorRamp {
+        ColorRamp::new(vec![
+            (0.0, Vector3::new(0.0, 0.0, 0.0)),
+            (1.0, Vector3::new(1.0, 1.0, 1.0)),
+        ])
+    }
+
+    pub fn get_color(&self, value: f64) -> Vector3<f64> {
+        let first = match self.stops.first() {
+            Some(first) => first,
+            None => return Vector3::repeat(value),
+        };
+
+        if value <= first.0 {
+            return first.1;
+        }
+
+        for pair in self.stops.windows(2) {
+            let ((start, from), (end, to)) = (pair[0], pair[1]);
+
+            if value <= end {
+                let t = if end > start {
+                    (value - start) / (end - start)
+                } else {
+                    1.0
+                };
+
+                return from + (to - from) * t;
+            }
+        }
+
+        self.stops.last().unwrap().1
+    }
+}
@@ -0,0 +1,63 @@
+use image::ImageFormat;
+
+// How the values stored in an image relate to linear light.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ColorSpace {
+    // Gamma encoded colors, as in most 8 and 16 bit albedo maps.
+    Srgb,
+    // Values used as they are, for HDR images and data such as normal or roughness maps.
+    Linear,
+}
+
+impl ColorSpace {
+    // Color space of an image file unless told otherwise: linear for the floating point formats,
+    // Radiance HDR and OpenEXR, and sRGB for all others.
+    pub fn of_file(file_path: &str) -> ColorSpace {
+        match ImageFormat::from_path(file_path) {
+            Ok(ImageFormat::Hdr | ImageFormat::OpenExr) => ColorSpace::Linear,
+            _ => ColorSpace::Srgb,
+        }
+    }
+
+    // Linear value of a stored channel in [0, 1].
+    pub fn decode(&self, value: f32) -> f32 {
+        match self {
+            ColorSpace::Srgb => {
+                if value <= 0.04045 {
+                    value / 12.92
+                } else {
+                    ((value + 0.055) / 1.055).powf(2.4)
+                }
+            }
+            ColorSpace::Linear => value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn srgb_decodes_along_the_standard_curve() {
+        assert_eq!(ColorSpace::Srgb.decode(0.0), 0.0);
+        assert!((ColorSpace::Srgb.decode(0.04045) - 0.04045 / 12.92).abs() < 1e-7);
+        assert!((ColorSpace::Srgb.decode(1.0) - 1.0).abs() < 1e-6);
+        assert!((ColorSpace::Srgb.decode(0.5) - 0.214).abs() < 1e-3);
+    }
+
+    #[test]
+    fn linear_keeps_values() {
+        for value in [0.0, 0.04045, 0.5, 1.0, 4.0] {
+            assert_eq!(ColorSpace::Linear.decode(value), value);
+        }
+    }
+
+    #[test]
+    fn floating_point_formats_are_linear() {
+        assert_eq!(ColorSpace::of_file("sky.hdr"), ColorSpace::Linear);
+        assert_eq!(ColorSpace::of_file("sky.exr"), ColorSpace::Linear);
+        assert_eq!(ColorSpace::of_file("albedo.png"), ColorSpace::Srgb);
+        assert_eq!(ColorSpace::of_file("albedo.jpg"), ColorSpace::Srgb);
+    }
+}
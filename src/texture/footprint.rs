@@ -0,0 +1,24 @@
+// Change of the texture coordinates across one pixel, along x and y of the image. It is all zero
+// when unknown, which looks up the finest detail.
+#[derive(Copy, Clone, Default)]
+pub struct Footprint {
+    pub dudx: f64,
+    pub dvdx: f64,
+    pub dudy: f64,
+    pub dvdy: f64,
+}
+
+impl Footprint {
+    // Footprint after the texture coordinates go through a linear map.
+    pub fn map(&self, transform: impl Fn(f64, f64) -> (f64, f64)) -> Footprint {
+        let (dudx, dvdx) = transform(self.dudx, self.dvdx);
+        let (dudy, dvdy) = transform(self.dudy, self.dvdy);
+
+        Footprint {
+            dudx,
+            dvdx,
+            dudy,
+            dvdy,
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use super::Footprint;
+use super::Texture;
+use super::TexturePoint;
+
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+
+// Adjusts the colors of another texture in HSV space. The hue shift is a fraction of a full turn,
+// and saturation and value are multipliers.
+pub struct HsvTexture {
+    texture: Arc<dyn Texture>,
+    hue_shift: f64,
+    saturation: f64,
+    value: f64,
+}
+
+impl HsvTexture {
+    pub fn arc(
+        texture: Arc<dyn Texture>,
+        hue_shift: f64,
+        saturation: f64,
+        value: f64,
+    ) -> Arc<HsvTexture> {
+        Arc::new(HsvTexture {
+            texture,
+            hue_shift,
+            saturation,
+            value,
+        })
+    }
+
+    fn rgb_to_hsv(rgb: Vector3<f64>) -> Vector3<f64> {
+        let max = rgb.max();
+        let chroma = max - rgb.min();
+
+        let hue = if chroma <= 0.0 {
+            0.0
+        } else if max == rgb.x {
+            ((rgb.y - rgb.z) / chroma).rem_euclid(6.0)
+        } else if max == rgb.y {
+            (rgb.z - rgb.x) / chroma + 2.0
+        } else {
+            (rgb.x - rgb.y) / chroma + 4.0
+        };
+        let saturation = if max > 0.0 { chroma / max } else { 0.0 };
+
+        Vector3::new(hue / 6.0, saturation, max)
+    }
+
+    fn hsv_to_rgb(hsv: Vector3<f64>) -> Vector3<f64> {
+        let channel = |n: f64| {
+            let k = (n + hsv.x * 6.0).rem_euclid(6.0);
+            hsv.z - hsv.z * hsv.y * k.min(4.0 - k).clamp(0.0, 1.0)
+        };
+
+        Vector3::new(channel(5.0), channel(3.0), channel(1.0))
+    }
+}
+
+impl Texture for HsvTexture {
+    fn get_color(&self, uv: (f64, f64), point: &TexturePoint) -> Vector3<f64> {
+        self.get_color_filtered(uv, point, &Footprint::default())
+    }
+
+    fn get_color_filtered(
+        &self,
+        uv: (f64, f64),
+        point: &TexturePoint,
+        footprint: &Footprint,
+    ) -> Vector3<f64> {
+        let hsv = HsvTexture::rgb_to_hsv(self.texture.get_color_filtered(uv, point, footprint));
+
+        HsvTexture::hsv_to_rgb(Vector3::new(
+            (hsv.x + self.hue_shift).rem_euclid(1.0),
+            (hsv.y * self.saturation).clamp(0.0, 1.0),
+            hsv.z * self.value,
+        ))
+    }
+}
@@ -1,43 +1,440 @@
+use super::image_data::ImageData;
+use super::ColorSpace;
+use super::Footprint;
 use super::Texture;
+use super::TexturePoint;
 
 use std::sync::Arc;
 
-use image::{io::Reader as ImageReader, ImageBuffer, Pixel, Rgb};
-use nalgebra::Vector3;
+use image::{codecs::hdr::HdrDecoder, io::Reader as ImageReader, ImageBuffer, ImageFormat, Rgba};
+use nalgebra::{Vector2, Vector3, Vector4};
 
+// Longest ratio between the axes of a footprint that anisotropic filtering follows with taps.
+const MAX_ANISOTROPY: f64 = 8.0;
+
+#[derive(Copy, Clone)]
+pub enum ImageFilter {
+    Nearest,
+    Bilinear,
+    // Catmull-Rom over the 4x4 nearest texels.
+    Bicubic,
+}
+
+// How lookups with a footprint pick and blend the levels of the mip pyramid.
+#[derive(Copy, Clone)]
+pub enum MipFilter {
+    // Always reads the full resolution image.
+    None,
+    // Blends the two levels closest to the longest axis of the footprint.
+    Trilinear,
+    // Takes several trilinear taps along the longest axis, at the level of the shortest one.
+    Anisotropic,
+}
+
+// What a lookup outside the [0, 1] range of one axis reads.
+#[derive(Copy, Clone)]
+pub enum WrapMode {
+    Repeat,
+    Clamp,
+    Mirror,
+    // The border color set on the texture.
+    Border,
+}
+
+impl WrapMode {
+    fn wrap(&self, index: i64, size: u32) -> Option<u32> {
+        let size = size as i64;
+
+        let wrapped = match self {
+            WrapMode::Repeat => index.rem_euclid(size),
+            WrapMode::Clamp => index.clamp(0, size - 1),
+            WrapMode::Mirror => {
+                let index = index.rem_euclid(2 * size);
+                if index < size {
+                    index
+                } else {
+                    2 * size - 1 - index
+                }
+            }
+            WrapMode::Border => {
+                if index < 0 || index >= size {
+                    return None;
+                }
+                index
+            }
+        };
+
+        Some(wrapped as u32)
+    }
+}
+
+// Any format the image crate decodes, stored as linear floating point.
 pub struct ImageTexture {
-    width: u32,
-    height: u32,
-    data: ImageBuffer<Rgb<u8>, Vec<u8>>,
+    data: Arc<ImageData>,
+    filter: ImageFilter,
+    mip_filter: MipFilter,
+    wrap_u: WrapMode,
+    wrap_v: WrapMode,
+    border: Vector4<f64>,
 }
 
 impl ImageTexture {
-    pub fn arc(file_path: String) -> Arc<ImageTexture> {
-        let image = ImageReader::open(file_path).unwrap().decode().unwrap();
-        let width = image.width();
-        let height = image.height();
-        let data = image.as_rgb8().unwrap();
+    // Decodes any format the image crate reads to linear RGBA.
+    pub fn load(file_path: &str, color_space: ColorSpace) -> ImageBuffer<Rgba<f32>, Vec<f32>> {
+        let reader = ImageReader::open(file_path).unwrap();
+
+        // Radiance files only keep their range through the dedicated decoder.
+        let mut data = if reader.format() == Some(ImageFormat::Hdr) {
+            let decoder = HdrDecoder::new(reader.into_inner()).unwrap();
+            let (width, height) = (decoder.metadata().width, decoder.metadata().height);
+            let pixels = decoder.read_image_hdr().unwrap();
+
+            ImageBuffer::from_fn(width, height, |x, y| {
+                let [r, g, b] = pixels[(y * width + x) as usize].0;
+                Rgba([r, g, b, 1.0])
+            })
+        } else {
+            reader.decode().unwrap().to_rgba32f()
+        };
+
+        for pixel in data.pixels_mut() {
+            for channel in &mut pixel.0[..3] {
+                *channel = color_space.decode(*channel);
+            }
+        }
+
+        data
+    }
+
+    // Texture over image data that may be shared with other textures.
+    pub(super) fn from_data(data: Arc<ImageData>) -> ImageTexture {
+        ImageTexture {
+            data,
+            filter: ImageFilter::Bilinear,
+            mip_filter: MipFilter::Trilinear,
+            wrap_u: WrapMode::Repeat,
+            wrap_v: WrapMode::Repeat,
+            border: Vector4::zeros(),
+        }
+    }
+
+    pub fn set_filter(&mut self, filter: ImageFilter) {
+        self.filter = filter;
+    }
+
+    pub fn set_mip_filter(&mut self, mip_filter: MipFilter) {
+        self.mip_filter = mip_filter;
+    }
+
+    pub fn set_wrap(&mut self, wrap_u: WrapMode, wrap_v: WrapMode) {
+        self.wrap_u = wrap_u;
+        self.wrap_v = wrap_v;
+    }
+
+    // Color and alpha read outside the image by the border wrap mode.
+    pub fn set_border(&mut self, color: Vector3<f64>, alpha: f64) {
+        self.border = Vector4::new(color.x, color.y, color.z, alpha);
+    }
+
+    fn get_texel(&self, level: usize, x: i64, y: i64) -> Vector4<f64> {
+        let data = &self.data.levels()[level];
+
+        match (
+            self.wrap_u.wrap(x, data.width()),
+            self.wrap_v.wrap(y, data.height()),
+        ) {
+            (Some(x), Some(y)) => data.get_pixel(x, y).0.map(|c| c as f64).into(),
+            _ => self.border,
+        }
+    }
+
+    // Filtered RGBA of one mip level at the given UV, which may lie outside [0, 1].
+    fn sample(&self, level: usize, (u, v): (f64, f64)) -> Vector4<f64> {
+        let u = if u.is_finite() { u } else { 0.0 };
+        let v = if v.is_finite() { v } else { 0.0 };
+
+        // Texel space, with texel centers at half integers and v pointing up.
+        let data = &self.data.levels()[level];
+        let x = u * data.width() as f64;
+        let y = (1.0 - v) * data.height() as f64;
+
+        if let ImageFilter::Nearest = self.filter {
+            return self.get_texel(level, x.floor() as i64, y.floor() as i64);
+        }
+
+        let (x, y) = (x - 0.5, y - 0.5);
+        let (x0, y0) = (x.floor() as i64, y.floor() as i64);
+        let (tx, ty) = (x - x.floor(), y - y.floor());
+
+        match self.filter {
+            ImageFilter::Bicubic => {
+                let weights_x = ImageTexture::catmull_rom(tx);
+                let weights_y = ImageTexture::catmull_rom(ty);
+
+                let mut color = Vector4::zeros();
+                for (j, weight_y) in weights_y.iter().enumerate() {
+                    for (i, weight_x) in weights_x.iter().enumerate() {
+                        color += weight_x
+                            * weight_y
+                            * self.get_texel(
+                                level,
+                                x0.saturating_add(i as i64 - 1),
+                                y0.saturating_add(j as i64 - 1),
+                            );
+                    }
+                }
+
+                // The negative lobes can overshoot below zero next to sharp edges.
+                color.map(|c| c.max(0.0))
+            }
+            _ => {
+                let x1 = x0.saturating_add(1);
+                let y1 = y0.saturating_add(1);
+
+                let top = self
+                    .get_texel(level, x0, y0)
+                    .lerp(&self.get_texel(level, x1, y0), tx);
+                let bottom = self
+                    .get_texel(level, x0, y1)
+                    .lerp(&self.get_texel(level, x1, y1), tx);
+
+                top.lerp(&bottom, ty)
+            }
+        }
+    }
+
+    // Blends the two mip levels around a footprint that is `width` texels of the full image wide.
+    fn sample_trilinear(&self, uv: (f64, f64), width: f64) -> Vector4<f64> {
+        let last = (self.data.levels().len() - 1) as f64;
+        let level = if width > 1.0 {
+            width.log2().min(last)
+        } else {
+            0.0
+        };
+
+        let lower = level.floor();
+        let color = self.sample(lower as usize, uv);
+        if level == lower {
+            return color;
+        }
+
+        color.lerp(&self.sample(lower as usize + 1, uv), level - lower)
+    }
+
+    fn sample_footprint(&self, uv: (f64, f64), footprint: &Footprint) -> Vector4<f64> {
+        let size = Vector2::new(
+            self.data.levels()[0].width() as f64,
+            self.data.levels()[0].height() as f64,
+        );
+        let axis_x = Vector2::new(footprint.dudx, footprint.dvdx);
+        let axis_y = Vector2::new(footprint.dudy, footprint.dvdy);
+
+        let (major, minor) =
+            if axis_x.component_mul(&size).norm() >= axis_y.component_mul(&size).norm() {
+                (axis_x, axis_y)
+            } else {
+                (axis_y, axis_x)
+            };
+        let major_width = major.component_mul(&size).norm();
+        let minor_width = minor.component_mul(&size).norm();
+
+        match self.mip_filter {
+            MipFilter::None => self.sample(0, uv),
+            MipFilter::Trilinear => self.sample_trilinear(uv, major_width),
+            MipFilter::Anisotropic => {
+                if !major_width.is_finite() || major_width <= 1.0 {
+                    return self.sample_trilinear(uv, major_width);
+                }
+
+                // Footprints longer than the maximum ratio are blurred across rather than along.
+                let minor_width = minor_width.max(major_width / MAX_ANISOTROPY);
+                let taps = (major_width / minor_width).ceil() as usize;
+
+                (0..taps)
+                    .map(|tap| {
+                        let offset = (tap as f64 + 0.5) / taps as f64 - 0.5;
+
+                        self.sample_trilinear(
+                            (uv.0 + offset * major.x, uv.1 + offset * major.y),
+                            minor_width,
+                        )
+                    })
+                    .sum::<Vector4<f64>>()
+                    / taps as f64
+            }
+        }
+    }
 
-        Arc::new(ImageTexture {
-            width,
-            height,
-            data: data.clone(),
-        })
+    fn catmull_rom(t: f64) -> [f64; 4] {
+        let t2 = t * t;
+        let t3 = t2 * t;
+
+        [
+            0.5 * (-t3 + 2.0 * t2 - t),
+            0.5 * (3.0 * t3 - 5.0 * t2 + 2.0),
+            0.5 * (-3.0 * t3 + 4.0 * t2 + t),
+            0.5 * (t3 - t2),
+        ]
     }
 }
 
 impl Texture for ImageTexture {
-    fn get_color(&self, (u, v): (f64, f64), _point: &Vector3<f64>) -> Vector3<f64> {
-        let x = (u * self.width as f64) as u32;
-        let y = ((1.0 - v) * self.height as f64) as u32;
-
-        let pixel = self.data.get_pixel(x, y).to_rgb();
-        let channels = pixel.channels();
-
-        Vector3::new(
-            channels[0] as f64 / 255.0,
-            channels[1] as f64 / 255.0,
-            channels[2] as f64 / 255.0,
-        )
+    fn get_color(&self, uv: (f64, f64), _point: &TexturePoint) -> Vector3<f64> {
+        self.sample(0, uv).xyz()
+    }
+
+    fn get_color_filtered(
+        &self,
+        uv: (f64, f64),
+        _point: &TexturePoint,
+        footprint: &Footprint,
+    ) -> Vector3<f64> {
+        self.sample_footprint(uv, footprint).xyz()
+    }
+
+    fn get_alpha(&self, uv: (f64, f64), _point: &TexturePoint) -> f64 {
+        self.sample(0, uv).w.clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env;
+    use std::fs::File;
+    use std::sync::OnceLock;
+
+    use crate::texture::TextureCache;
+
+    use image::codecs::hdr::HdrEncoder;
+    use image::Rgb;
+
+    fn wrapped(mode: WrapMode) -> Vec<Option<u32>> {
+        (-5..9).map(|index| mode.wrap(index, 4)).collect()
+    }
+
+    #[test]
+    fn repeat_tiles_the_image() {
+        let expected = [3, 0, 1, 2, 3, 0, 1, 2, 3, 0, 1, 2, 3, 0];
+
+        assert_eq!(wrapped(WrapMode::Repeat), expected.map(Some));
+    }
+
+    #[test]
+    fn clamp_extends_the_edges() {
+        let expected = [0, 0, 0, 0, 0, 0, 1, 2, 3, 3, 3, 3, 3, 3];
+
+        assert_eq!(wrapped(WrapMode::Clamp), expected.map(Some));
+    }
+
+    #[test]
+    fn mirror_reflects_at_each_edge() {
+        let expected = [3, 3, 2, 1, 0, 0, 1, 2, 3, 3, 2, 1, 0, 0];
+
+        assert_eq!(wrapped(WrapMode::Mirror), expected.map(Some));
+    }
+
+    #[test]
+    fn border_reads_nothing_outside() {
+        let inside = [0, 1, 2, 3].map(Some);
+
+        assert_eq!(&wrapped(WrapMode::Border)[5..9], inside);
+        assert!(wrapped(WrapMode::Border)[..5].iter().all(Option::is_none));
+        assert!(wrapped(WrapMode::Border)[9..].iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn extreme_indices_stay_in_range() {
+        for mode in [WrapMode::Repeat, WrapMode::Clamp, WrapMode::Mirror] {
+            for index in [i64::MIN, i64::MAX] {
+                assert!(mode.wrap(index, 4).is_some_and(|index| index < 4));
+            }
+        }
+    }
+
+    // First channel of an image written to a temporary file and loaded with its default space.
+    fn loaded(file_name: &str, write: impl FnOnce(&str)) -> f32 {
+        let file_path = env::temp_dir().join(file_name);
+        let file_path = file_path.to_str().unwrap();
+        write(file_path);
+
+        ImageTexture::load(file_path, ColorSpace::of_file(file_path))
+            .get_pixel(0, 0)
+            .0[0]
+    }
+
+    #[test]
+    fn eight_bit_images_load_as_srgb() {
+        let value = loaded("rustic_eight_bit.png", |file_path| {
+            image::save_buffer(file_path, &[128, 128, 128], 1, 1, image::ColorType::Rgb8).unwrap()
+        });
+
+        assert!((value - ColorSpace::Srgb.decode(128.0 / 255.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn radiance_images_load_as_linear() {
+        let value = loaded("rustic_radiance.hdr", |file_path| {
+            HdrEncoder::new(File::create(file_path).unwrap())
+                .encode(&[Rgb([0.5, 0.5, 0.5])], 1, 1)
+                .unwrap()
+        });
+
+        assert_eq!(value, 0.5);
+    }
+
+    // Lookup in the middle of a white column of 16x16 one texel wide vertical stripes, with a
+    // footprint `length` texels long along the stripes and a fraction of a texel across them.
+    fn along_stripes(mip_filter: MipFilter, length: f64) -> f64 {
+        static FILE_PATH: OnceLock<String> = OnceLock::new();
+
+        let file_path = FILE_PATH.get_or_init(|| {
+            let file_path = env::temp_dir().join("rustic_stripes.png");
+            let file_path = file_path.to_str().unwrap().to_string();
+
+            let pixels: Vec<u8> = (0..16 * 16)
+                .flat_map(|i| [if i % 2 == 0 { 255 } else { 0 }; 3])
+                .collect();
+            image::save_buffer(&file_path, &pixels, 16, 16, image::ColorType::Rgb8).unwrap();
+
+            file_path
+        });
+
+        let mut texture = TextureCache::new().get(file_path, Some(ColorSpace::Linear));
+        texture.set_mip_filter(mip_filter);
+
+        let footprint = Footprint {
+            dudx: 0.01 / 16.0,
+            dvdx: 0.0,
+            dudy: 0.0,
+            dvdy: length / 16.0,
+        };
+        let point = TexturePoint::new(Vector3::zeros(), Vector3::zeros());
+
+        texture
+            .get_color_filtered((2.5 / 16.0, 0.5), &point, &footprint)
+            .x
+    }
+
+    #[test]
+    fn no_mip_filter_reads_the_full_image() {
+        assert_eq!(along_stripes(MipFilter::None, 8.0), 1.0);
+    }
+
+    #[test]
+    fn trilinear_blurs_by_the_longest_axis() {
+        assert!((along_stripes(MipFilter::Trilinear, 8.0) - 0.5).abs() < 0.1);
+    }
+
+    #[test]
+    fn anisotropic_keeps_the_detail_across_the_footprint() {
+        assert!((along_stripes(MipFilter::Anisotropic, 8.0) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn anisotropic_blurs_across_beyond_the_maximum_ratio() {
+        // A footprint 64 texels long is taken as 64 / MAX_ANISOTROPY texels wide.
+        assert!((along_stripes(MipFilter::Anisotropic, 64.0) - 0.5).abs() < 0.1);
     }
 }
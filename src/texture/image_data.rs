@@ -0,0 +1,57 @@
+use super::ColorSpace;
+use super::ImageTexture;
+
+use std::mem::size_of;
+use std::sync::OnceLock;
+
+use image::{imageops, imageops::FilterType, ImageBuffer, Rgba};
+
+type MipLevel = ImageBuffer<Rgba<f32>, Vec<f32>>;
+
+// Decoded mip pyramid of an image file, shared by every texture that reads it. It is loaded the
+// first time it is needed unless loaded up front.
+pub struct ImageData {
+    file_path: String,
+    color_space: ColorSpace,
+    levels: OnceLock<Vec<MipLevel>>,
+}
+
+impl ImageData {
+    pub fn new(file_path: String, color_space: ColorSpace) -> ImageData {
+        ImageData {
+            file_path,
+            color_space,
+            levels: OnceLock::new(),
+        }
+    }
+
+    // From the full resolution image down to a single texel. The levels are built from linear
+    // data, so that they average light rather than codes.
+    pub fn levels(&self) -> &[MipLevel] {
+        self.levels.get_or_init(|| {
+            let mut levels = vec![ImageTexture::load(&self.file_path, self.color_space)];
+            loop {
+                let last = levels.last().unwrap();
+                if last.width() == 1 && last.height() == 1 {
+                    break;
+                }
+
+                let width = (last.width() / 2).max(1);
+                let height = (last.height() / 2).max(1);
+                levels.push(imageops::resize(last, width, height, FilterType::Triangle));
+            }
+
+            levels
+        })
+    }
+
+    // Bytes taken by the pyramid, or zero while it is not loaded.
+    pub fn memory_usage(&self) -> usize {
+        self.levels.get().map_or(0, |levels| {
+            levels
+                .iter()
+                .map(|level| level.as_raw().len() * size_of::<f32>())
+                .sum()
+        })
+    }
+}
@@ -0,0 +1,36 @@
+use super::Footprint;
+use super::Texture;
+use super::TexturePoint;
+
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+
+pub struct InvertTexture {
+    texture: Arc<dyn Texture>,
+}
+
+impl InvertTexture {
+    pub fn arc(texture: Arc<dyn Texture>) -> Arc<InvertTexture> {
+        Arc::new(InvertTexture { texture })
+    }
+}
+
+impl Texture for InvertTexture {
+    fn get_color(&self, uv: (f64, f64), point: &TexturePoint) -> Vector3<f64> {
+        self.get_color_filtered(uv, point, &Footprint::default())
+    }
+
+    fn get_color_filtered(
+        &self,
+        uv: (f64, f64),
+        point: &TexturePoint,
+        footprint: &Footprint,
+    ) -> Vector3<f64> {
+        Vector3::repeat(1.0) - self.texture.get_color_filtered(uv, point, footprint)
+    }
+
+    fn get_value(&self, uv: (f64, f64), point: &TexturePoint) -> f64 {
+        1.0 - self.texture.get_value(uv, point)
+    }
+}
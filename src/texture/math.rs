@@ -0,0 +1,78 @@
+use super::Footprint;
+use super::Texture;
+use super::TexturePoint;
+
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+
+#[derive(Copy, Clone)]
+pub enum MathOperation {
+    Add,
+    Subtract,
+    Multiply,
+}
+
+impl MathOperation {
+    fn apply(&self, a: f64, b: f64) -> f64 {
+        match self {
+            MathOperation::Add => a + b,
+            MathOperation::Subtract => a - b,
+            MathOperation::Multiply => a * b,
+        }
+    }
+}
+
+// Combines two textures channel by channel, such as tinting a pattern or adding grime to it.
+pub struct MathTexture {
+    operation: MathOperation,
+    first: Arc<dyn Texture>,
+    second: Arc<dyn Texture>,
+}
+
+impl MathTexture {
+    pub fn arc(
+        operation: MathOperation,
+        first: Arc<dyn Texture>,
+        second: Arc<dyn Texture>,
+    ) -> Arc<MathTexture> {
+        Arc::new(MathTexture {
+            operation,
+            first,
+            second,
+        })
+    }
+
+    pub fn arc_add(first: Arc<dyn Texture>, second: Arc<dyn Texture>) -> Arc<MathTexture> {
+        MathTexture::arc(MathOperation::Add, first, second)
+    }
+
+    pub fn arc_multiply(first: Arc<dyn Texture>, second: Arc<dyn Texture>) -> Arc<MathTexture> {
+        MathTexture::arc(MathOperation::Multiply, first, second)
+    }
+}
+
+impl Texture for MathTexture {
+    fn get_color(&self, uv: (f64, f64), point: &TexturePoint) -> Vector3<f64> {
+        self.get_color_filtered(uv, point, &Footprint::default())
+    }
+
+    fn get_color_filtered(
+        &self,
+        uv: (f64, f64),
+        point: &TexturePoint,
+        footprint: &Footprint,
+    ) -> Vector3<f64> {
+        self.first.get_color_filtered(uv, point, footprint).zip_map(
+            &self.second.get_color_filtered(uv, point, footprint),
+            |a, b| self.operation.apply(a, b),
+        )
+    }
+
+    fn get_value(&self, uv: (f64, f64), point: &TexturePoint) -> f64 {
+        self.operation.apply(
+            self.first.get_value(uv, point),
+            self.second.get_value(uv, point),
+        )
+    }
+}
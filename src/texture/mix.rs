@@ -0,0 +1,62 @@
+use super::Footprint;
+use super::Texture;
+use super::TexturePoint;
+
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+
+// Blends two textures, with the value of a third as the weight of the second.
+pub struct MixTexture {
+    first: Arc<dyn Texture>,
+    second: Arc<dyn Texture>,
+    factor: Arc<dyn Texture>,
+}
+
+impl MixTexture {
+    pub fn arc(
+        first: Arc<dyn Texture>,
+        second: Arc<dyn Texture>,
+        factor: Arc<dyn Texture>,
+    ) -> Arc<MixTexture> {
+        Arc::new(MixTexture {
+            first,
+            second,
+            factor,
+        })
+    }
+
+    fn factor(&self, uv: (f64, f64), point: &TexturePoint) -> f64 {
+        self.factor.get_value(uv, point).clamp(0.0, 1.0)
+    }
+}
+
+impl Texture for MixTexture {
+    fn get_color(&self, uv: (f64, f64), point: &TexturePoint) -> Vector3<f64> {
+        self.get_color_filtered(uv, point, &Footprint::default())
+    }
+
+    fn get_color_filtered(
+        &self,
+        uv: (f64, f64),
+        point: &TexturePoint,
+        footprint: &Footprint,
+    ) -> Vector3<f64> {
+        let factor = self.factor(uv, point);
+
+        self.first.get_color_filtered(uv, point, footprint) * (1.0 - factor)
+            + self.second.get_color_filtered(uv, point, footprint) * factor
+    }
+
+    fn get_alpha(&self, uv: (f64, f64), point: &TexturePoint) -> f64 {
+        let factor = self.factor(uv, point);
+
+        self.first.get_alpha(uv, point) * (1.0 - factor) + self.second.get_alpha(uv, point) * factor
+    }
+
+    fn get_value(&self, uv: (f64, f64), point: &TexturePoint) -> f64 {
+        let factor = self.factor(uv, point);
+
+        self.first.get_value(uv, point) * (1.0 - factor) + self.second.get_value(uv, point) * factor
+    }
+}
@@ -0,0 +1,107 @@
+use super::perlin::Perlin;
+use super::ColorRamp;
+use super::Texture;
+use super::{TexturePoint, TextureSpace};
+
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+
+// How far marble veins and wood rings are pushed around by turbulence.
+const DISTORTION: f64 = 10.0;
+
+#[derive(Copy, Clone)]
+pub enum NoiseKind {
+    Perlin,
+    Fbm,
+    Turbulence,
+    Marble,
+    Wood,
+}
+
+// Procedural solid texture evaluated on the 3D point of the hit, mapped through a color ramp.
+pub struct NoiseTexture {
+    perlin: Perlin,
+    space: TextureSpace,
+    kind: NoiseKind,
+    frequency: f64,
+    octaves: u32,
+    lacunarity: f64,
+    gain: f64,
+    ramp: ColorRamp,
+}
+
+impl NoiseTexture {
+    pub fn new(
+        kind: NoiseKind,
+        frequency: f64,
+        octaves: u32,
+        lacunarity: f64,
+        gain: f64,
+        ramp: ColorRamp,
+    ) -> NoiseTexture {
+        NoiseTexture {
+            perlin: Perlin::new(),
+            space: TextureSpace::Object,
+            kind,
+            frequency,
+            octaves,
+            lacunarity,
+            gain,
+            ramp,
+        }
+    }
+
+    pub fn arc(
+        kind: NoiseKind,
+        frequency: f64,
+        octaves: u32,
+        lacunarity: f64,
+        gain: f64,
+        ramp: ColorRamp,
+    ) -> Arc<NoiseTexture> {
+        Arc::new(NoiseTexture::new(
+            kind, frequency, octaves, lacunarity, gain, ramp,
+        ))
+    }
+
+    pub fn set_space(&mut self, space: TextureSpace) {
+        self.space = space;
+    }
+
+    // Noise value in [0, 1] before the color ramp.
+    fn value(&self, uv: (f64, f64), point: &TexturePoint) -> f64 {
+        let scaled = self.space.coordinates(uv, point) * self.frequency;
+
+        let turbulence = || {
+            self.perlin
+                .turbulence(&scaled, self.octaves, self.lacunarity, self.gain)
+        };
+
+        match self.kind {
+            NoiseKind::Perlin => 0.5 * (1.0 + self.perlin.noise(&scaled)),
+            NoiseKind::Fbm => {
+                0.5 * (1.0
+                    + self
+                        .perlin
+                        .fbm(&scaled, self.octaves, self.lacunarity, self.gain))
+            }
+            NoiseKind::Turbulence => turbulence(),
+            NoiseKind::Marble => 0.5 * (1.0 + (scaled.z + DISTORTION * turbulence()).sin()),
+            NoiseKind::Wood => {
+                let radius = (scaled.x * scaled.x + scaled.z * scaled.z).sqrt();
+                (radius + 0.1 * DISTORTION * turbulence()).fract()
+            }
+        }
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn get_color(&self, uv: (f64, f64), point: &TexturePoint) -> Vector3<f64> {
+        self.ramp.get_color(self.value(uv, point))
+    }
+
+    fn get_value(&self, uv: (f64, f64), point: &TexturePoint) -> f64 {
+        self.value(uv, point)
+    }
+}
@@ -0,0 +1,118 @@
+use crate::vector3_traits::Helpers;
+
+use nalgebra::Vector3;
+use rand::seq::SliceRandom;
+
+const POINT_COUNT: usize = 256;
+
+// Gradient noise with random unit gradients at the integer lattice points, and the sums of octaves
+// built on it.
+pub struct Perlin {
+    gradients: Vec<Vector3<f64>>,
+    permutation_x: Vec<usize>,
+    permutation_y: Vec<usize>,
+    permutation_z: Vec<usize>,
+}
+
+impl Perlin {
+    pub fn new() -> Perlin {
+        let gradients = (0..POINT_COUNT)
+            .map(|_| Vector3::new_random_in_range(-1.0, 1.0).normalize())
+            .collect();
+
+        Perlin {
+            gradients,
+            permutation_x: Perlin::permutation(),
+            permutation_y: Perlin::permutation(),
+            permutation_z: Perlin::permutation(),
+        }
+    }
+
+    fn permutation() -> Vec<usize> {
+        let mut permutation: Vec<usize> = (0..POINT_COUNT).collect();
+        permutation.shuffle(&mut rand::thread_rng());
+
+        permutation
+    }
+
+    // Noise in [-1, 1] that is zero at every lattice point.
+    pub fn noise(&self, point: &Vector3<f64>) -> f64 {
+        let floor = point.map(f64::floor);
+        let fraction = point - floor;
+
+        let mut corners = [[[Vector3::zeros(); 2]; 2]; 2];
+        for (di, plane) in corners.iter_mut().enumerate() {
+            for (dj, row) in plane.iter_mut().enumerate() {
+                for (dk, corner) in row.iter_mut().enumerate() {
+                    let i = (floor.x as i64 + di as i64) as usize & (POINT_COUNT - 1);
+                    let j = (floor.y as i64 + dj as i64) as usize & (POINT_COUNT - 1);
+                    let k = (floor.z as i64 + dk as i64) as usize & (POINT_COUNT - 1);
+
+                    *corner = self.gradients
+                        [self.permutation_x[i] ^ self.permutation_y[j] ^ self.permutation_z[k]];
+                }
+            }
+        }
+
+        // Hermite smoothing of the fraction, then trilinear blending of the gradient ramps.
+        let smooth = fraction.map(|f| f * f * (3.0 - 2.0 * f));
+
+        let mut accumulated = 0.0;
+        for (i, plane) in corners.iter().enumerate() {
+            for (j, row) in plane.iter().enumerate() {
+                for (k, corner) in row.iter().enumerate() {
+                    let (fi, fj, fk) = (i as f64, j as f64, k as f64);
+                    let weight = Vector3::new(fraction.x - fi, fraction.y - fj, fraction.z - fk);
+
+                    accumulated += (fi * smooth.x + (1.0 - fi) * (1.0 - smooth.x))
+                        * (fj * smooth.y + (1.0 - fj) * (1.0 - smooth.y))
+                        * (fk * smooth.z + (1.0 - fk) * (1.0 - smooth.z))
+                        * Vector3::dot(corner, &weight);
+                }
+            }
+        }
+
+        accumulated.clamp(-1.0, 1.0)
+    }
+
+    // Fractal Brownian motion: octaves of noise at increasing frequency and decreasing amplitude,
+    // normalized back to [-1, 1].
+    pub fn fbm(&self, point: &Vector3<f64>, octaves: u32, lacunarity: f64, gain: f64) -> f64 {
+        self.octaves(point, octaves, lacunarity, gain, |noise| noise)
+    }
+
+    // Like fractal Brownian motion but summing the absolute value of each octave, in [0, 1].
+    pub fn turbulence(
+        &self,
+        point: &Vector3<f64>,
+        octaves: u32,
+        lacunarity: f64,
+        gain: f64,
+    ) -> f64 {
+        self.octaves(point, octaves, lacunarity, gain, f64::abs)
+    }
+
+    fn octaves(
+        &self,
+        point: &Vector3<f64>,
+        octaves: u32,
+        lacunarity: f64,
+        gain: f64,
+        shape: impl Fn(f64) -> f64,
+    ) -> f64 {
+        let mut sum = 0.0;
+        let mut total_amplitude = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+
+        for _ in 0..octaves.max(1) {
+            sum += amplitude * shape(self.noise(&(point * frequency)));
+            total_amplitude += amplitude;
+
+            amplitude *= gain;
+            frequency *= lacunarity;
+        }
+
+        sum / total_amplitude
+    }
+}
@@ -0,0 +1,25 @@
+use super::ColorRamp;
+use super::Texture;
+use super::TexturePoint;
+
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+
+// Colors the value of another texture through a color ramp.
+pub struct RampTexture {
+    texture: Arc<dyn Texture>,
+    ramp: ColorRamp,
+}
+
+impl RampTexture {
+    pub fn arc(texture: Arc<dyn Texture>, ramp: ColorRamp) -> Arc<RampTexture> {
+        Arc::new(RampTexture { texture, ramp })
+    }
+}
+
+impl Texture for RampTexture {
+    fn get_color(&self, uv: (f64, f64), point: &TexturePoint) -> Vector3<f64> {
+        self.ramp.get_color(self.texture.get_value(uv, point))
+    }
+}
@@ -1,4 +1,5 @@
 use super::Texture;
+use super::TexturePoint;
 
 use std::sync::Arc;
 
@@ -15,7 +16,7 @@ impl SolidColorTexture {
 }
 
 impl Texture for SolidColorTexture {
-    fn get_color(&self, (_u, _v): (f64, f64), _point: &Vector3<f64>) -> Vector3<f64> {
+    fn get_color(&self, (_u, _v): (f64, f64), _point: &TexturePoint) -> Vector3<f64> {
         self.color
     }
 }
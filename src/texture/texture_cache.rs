@@ -0,0 +1,115 @@
+use super::image_data::ImageData;
+use super::ColorSpace;
+use super::ImageTexture;
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+type Key = (String, ColorSpace);
+
+// Image textures that share their decoded data by path and color space, so that every file is
+// read once however many materials use it.
+pub struct TextureCache {
+    images: Mutex<HashMap<Key, Arc<ImageData>>>,
+    lazy: bool,
+}
+
+impl TextureCache {
+    pub fn new() -> TextureCache {
+        TextureCache {
+            images: Mutex::new(HashMap::new()),
+            lazy: false,
+        }
+    }
+
+    // Defers decoding each image until a texture first samples it.
+    pub fn set_lazy(&mut self, lazy: bool) {
+        self.lazy = lazy;
+    }
+
+    // Texture with its own filtering and wrapping over the shared image. Without a color space,
+    // the one of the file format is used. Missing files are reported here even when decoding is
+    // deferred.
+    pub fn get(&self, file_path: &str, color_space: Option<ColorSpace>) -> ImageTexture {
+        if !Path::new(file_path).is_file() {
+            panic!("Texture image {file_path} not found");
+        }
+
+        let color_space = color_space.unwrap_or_else(|| ColorSpace::of_file(file_path));
+        let data = Arc::clone(
+            self.images
+                .lock()
+                .unwrap()
+                .entry((file_path.to_string(), color_space))
+                .or_insert_with(|| Arc::new(ImageData::new(file_path.to_string(), color_space))),
+        );
+
+        if !self.lazy {
+            data.levels();
+        }
+
+        ImageTexture::from_data(data)
+    }
+
+    pub fn arc(&self, file_path: &str) -> Arc<ImageTexture> {
+        Arc::new(self.get(file_path, None))
+    }
+
+    // Image holding data rather than colors, such as a normal, height or roughness map.
+    pub fn arc_linear(&self, file_path: &str) -> Arc<ImageTexture> {
+        Arc::new(self.get(file_path, Some(ColorSpace::Linear)))
+    }
+
+    // Number of images and bytes of decoded data loaded so far.
+    pub fn memory_usage(&self) -> (usize, usize) {
+        let images = self.images.lock().unwrap();
+        let loaded = images
+            .values()
+            .filter(|data| data.memory_usage() > 0)
+            .count();
+
+        (
+            loaded,
+            images.values().map(|data| data.memory_usage()).sum(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lazy() -> TextureCache {
+        let mut cache = TextureCache::new();
+        cache.set_lazy(true);
+
+        cache
+    }
+
+    #[test]
+    fn default_color_space_shares_the_entry() {
+        let cache = lazy();
+
+        cache.get("resources/earth.jpg", None);
+        cache.get("resources/earth.jpg", Some(ColorSpace::Srgb));
+        assert_eq!(cache.images.lock().unwrap().len(), 1);
+
+        cache.get("resources/earth.jpg", Some(ColorSpace::Linear));
+        assert_eq!(cache.images.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn lazy_loading_decodes_nothing_up_front() {
+        let cache = lazy();
+
+        cache.arc("resources/earth.jpg");
+        assert_eq!(cache.memory_usage(), (0, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "not found")]
+    fn lazy_loading_still_reports_missing_files() {
+        lazy().arc("resources/missing.png");
+    }
+}
@@ -0,0 +1,39 @@
+use nalgebra::Vector3;
+
+// Coordinates that solid and procedural textures are evaluated on.
+#[derive(Copy, Clone)]
+pub enum TextureSpace {
+    // Position on the shape before its transforms, so that the pattern moves with the object.
+    Object,
+    World,
+    // Texture coordinates of the hit, as (u, v, 0).
+    Uv,
+}
+
+impl TextureSpace {
+    pub fn coordinates(&self, (u, v): (f64, f64), point: &TexturePoint) -> Vector3<f64> {
+        match self {
+            TextureSpace::Object => point.object,
+            TextureSpace::World => point.world,
+            TextureSpace::Uv => Vector3::new(u, v, 0.0),
+        }
+    }
+}
+
+// Position of a hit in world space and in the space of the shape before any transforms.
+#[derive(Copy, Clone)]
+pub struct TexturePoint {
+    pub world: Vector3<f64>,
+    pub object: Vector3<f64>,
+}
+
+impl TexturePoint {
+    pub fn new(world: Vector3<f64>, object: Vector3<f64>) -> TexturePoint {
+        TexturePoint { world, object }
+    }
+
+    // Position after both spaces go through the same map.
+    pub fn map(&self, transform: impl Fn(&Vector3<f64>) -> Vector3<f64>) -> TexturePoint {
+        TexturePoint::new(transform(&self.world), transform(&self.object))
+    }
+}
@@ -0,0 +1,106 @@
+use super::Footprint;
+use super::Texture;
+use super::TexturePoint;
+
+use std::sync::Arc;
+
+use nalgebra::{Rotation2, Rotation3, Unit, Vector2, Vector3};
+
+// Scales, rotates and then offsets the UV and 3D coordinates before looking up another texture.
+pub struct TransformTexture {
+    texture: Arc<dyn Texture>,
+    uv_scale: Vector2<f64>,
+    uv_rotation: Rotation2<f64>,
+    uv_offset: Vector2<f64>,
+    point_scale: Vector3<f64>,
+    point_rotation: Rotation3<f64>,
+    point_offset: Vector3<f64>,
+}
+
+impl TransformTexture {
+    pub fn new(texture: Arc<dyn Texture>) -> TransformTexture {
+        TransformTexture {
+            texture,
+            uv_scale: Vector2::repeat(1.0),
+            uv_rotation: Rotation2::identity(),
+            uv_offset: Vector2::zeros(),
+            point_scale: Vector3::repeat(1.0),
+            point_rotation: Rotation3::identity(),
+            point_offset: Vector3::zeros(),
+        }
+    }
+
+    // Repeats the texture `u` times horizontally and `v` times vertically.
+    pub fn arc_tiled(texture: Arc<dyn Texture>, u: f64, v: f64) -> Arc<TransformTexture> {
+        let mut transform = TransformTexture::new(texture);
+        transform.set_uv_scale(u, v);
+
+        Arc::new(transform)
+    }
+
+    pub fn set_uv_scale(&mut self, u: f64, v: f64) {
+        self.uv_scale = Vector2::new(u, v);
+    }
+
+    pub fn set_uv_rotation(&mut self, degrees: f64) {
+        self.uv_rotation = Rotation2::new(degrees.to_radians());
+    }
+
+    pub fn set_uv_offset(&mut self, u: f64, v: f64) {
+        self.uv_offset = Vector2::new(u, v);
+    }
+
+    pub fn set_point_scale(&mut self, scale: Vector3<f64>) {
+        self.point_scale = scale;
+    }
+
+    pub fn set_point_rotation(&mut self, axis: Vector3<f64>, degrees: f64) {
+        self.point_rotation =
+            Rotation3::from_axis_angle(&Unit::new_normalize(axis), degrees.to_radians());
+    }
+
+    pub fn set_point_offset(&mut self, offset: Vector3<f64>) {
+        self.point_offset = offset;
+    }
+
+    fn transform(&self, (u, v): (f64, f64), point: &TexturePoint) -> ((f64, f64), TexturePoint) {
+        let uv =
+            self.uv_rotation * Vector2::new(u, v).component_mul(&self.uv_scale) + self.uv_offset;
+        let point = point.map(|point| {
+            self.point_rotation * point.component_mul(&self.point_scale) + self.point_offset
+        });
+
+        ((uv.x, uv.y), point)
+    }
+}
+
+impl Texture for TransformTexture {
+    fn get_color(&self, uv: (f64, f64), point: &TexturePoint) -> Vector3<f64> {
+        self.get_color_filtered(uv, point, &Footprint::default())
+    }
+
+    fn get_color_filtered(
+        &self,
+        uv: (f64, f64),
+        point: &TexturePoint,
+        footprint: &Footprint,
+    ) -> Vector3<f64> {
+        let (uv, point) = self.transform(uv, point);
+        let footprint = footprint.map(|du, dv| {
+            let derivative = self.uv_rotation * Vector2::new(du, dv).component_mul(&self.uv_scale);
+            (derivative.x, derivative.y)
+        });
+
+        self.texture.get_color_filtered(uv, &point, &footprint)
+    }
+
+    fn get_alpha(&self, uv: (f64, f64), point: &TexturePoint) -> f64 {
+        let (uv, point) = self.transform(uv, point);
+        self.texture.get_alpha(uv, &point)
+    }
+
+    fn get_value(&self, uv: (f64, f64), point: &TexturePoint) -> f64 {
+        let (uv, point) = self.transform(uv, point);
+        self.texture.get_value(uv, &point)
+    }
+}
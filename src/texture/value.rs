@@ -0,0 +1,27 @@
+use super::Texture;
+use super::TexturePoint;
+
+use std::sync::Arc;
+
+use nalgebra::Vector3;
+
+// Constant scalar, for inputs such as roughness, mix factors or heights.
+pub struct ValueTexture {
+    value: f64,
+}
+
+impl ValueTexture {
+    pub fn arc(value: f64) -> Arc<ValueTexture> {
+        Arc::new(ValueTexture { value })
+    }
+}
+
+impl Texture for ValueTexture {
+    fn get_color(&self, _uv: (f64, f64), _point: &TexturePoint) -> Vector3<f64> {
+        Vector3::repeat(self.value)
+    }
+
+    fn get_value(&self, _uv: (f64, f64), _point: &TexturePoint) -> f64 {
+        self.value
+    }
+}
@@ -3,3 +3,180 @@ pub use translate::Translate;
 
 mod rotate;
 pub use rotate::RotateY;
+
+mod flip_face;
+pub use flip_face::FlipFace;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::hittable::{HitRecord, Hittable, HittableList};
+    use crate::material::{DielectricMaterial, EmissiveMaterial, RefractiveIndex};
+    use crate::ray::Ray;
+    use crate::shape::{RectangleXY, Sphere};
+    use crate::texture::{CheckerTexture, SolidColorTexture, Texture, TextureSpace};
+
+    use std::sync::Arc;
+
+    use nalgebra::{Rotation3, Vector3};
+
+    const ABSORPTION: Vector3<f64> = Vector3::new(1.0, 0.5, 0.25);
+
+    // Light from a white wall seen straight through the middle of an absorbing unit sphere at the
+    // origin. A refractive index of one keeps the path on a single deterministic line.
+    fn transmitted(sphere: impl Fn(Arc<DielectricMaterial>) -> Arc<dyn Hittable>) -> Vector3<f64> {
+        let mut glass = DielectricMaterial::new(RefractiveIndex::Constant(1.0));
+        glass.set_absorption(ABSORPTION);
+
+        let mut world = HittableList::new();
+        world.add(sphere(Arc::new(glass)));
+        world.add(RectangleXY::arc(
+            (-10.0, 10.0),
+            (-10.0, 10.0),
+            -5.0,
+            EmissiveMaterial::arc(SolidColorTexture::arc(Vector3::repeat(1.0))),
+        ));
+        let world: Arc<dyn Hittable> = Arc::new(world);
+
+        let ray = Ray::new(Vector3::new(0.0, 0.0, 5.0), Vector3::new(0.0, 0.0, -1.0));
+        Ray::ray_color(&ray, &world, &Vector3::zeros(), None, 16)
+    }
+
+    fn assert_close(a: Vector3<f64>, b: Vector3<f64>) {
+        assert!((a - b).abs().max() < 1e-9, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn untransformed_sphere_absorbs_along_its_diameter() {
+        let color = transmitted(|glass| Sphere::arc(Vector3::zeros(), 1.0, glass));
+
+        assert_close(color, (-2.0 * ABSORPTION).map(f64::exp));
+    }
+
+    #[test]
+    fn translated_sphere_matches_untransformed() {
+        let untransformed = transmitted(|glass| Sphere::arc(Vector3::zeros(), 1.0, glass));
+        let translated = transmitted(|glass| {
+            Translate::arc(
+                Vector3::new(2.0, 0.0, 0.0),
+                Sphere::arc(Vector3::new(-2.0, 0.0, 0.0), 1.0, glass),
+            )
+        });
+
+        assert_close(translated, untransformed);
+    }
+
+    #[test]
+    fn rotated_sphere_matches_untransformed() {
+        let untransformed = transmitted(|glass| Sphere::arc(Vector3::zeros(), 1.0, glass));
+        let rotated =
+            transmitted(|glass| RotateY::arc(90.0, Sphere::arc(Vector3::zeros(), 1.0, glass)));
+
+        assert_close(rotated, untransformed);
+    }
+
+    #[test]
+    fn rotation_takes_world_directions_to_object_space() {
+        let white = SolidColorTexture::arc(Vector3::repeat(1.0));
+        let sphere = Translate::arc(
+            Vector3::new(1.0, 0.0, 0.0),
+            RotateY::arc(
+                30.0,
+                RotateY::arc(
+                    45.0,
+                    Sphere::arc(Vector3::zeros(), 1.0, EmissiveMaterial::arc(white)),
+                ),
+            ),
+        );
+
+        let ray = Ray::new(Vector3::new(1.5, 0.3, 5.0), Vector3::new(0.0, 0.0, -1.0));
+        let mut hit = HitRecord::new();
+        assert!(sphere.hit(&ray, 0.0001, f64::MAX, &mut hit));
+
+        // On the unit sphere the outward normal in object space is the object point itself.
+        assert_close(hit.object_rotation * hit.normal, hit.object_point);
+    }
+
+    // Unit sphere around (0.5, 0, 0), turned by 60 degrees and then moved to (3, 1, -2).
+    fn moved_sphere() -> Arc<dyn Hittable> {
+        let white = SolidColorTexture::arc(Vector3::repeat(1.0));
+
+        Translate::arc(
+            Vector3::new(3.0, 1.0, -2.0),
+            RotateY::arc(
+                60.0,
+                Sphere::arc(
+                    Vector3::new(0.5, 0.0, 0.0),
+                    1.0,
+                    EmissiveMaterial::arc(white),
+                ),
+            ),
+        )
+    }
+
+    #[test]
+    fn object_point_undoes_the_transforms() {
+        let rotation = Rotation3::from_axis_angle(&Vector3::y_axis(), 60f64.to_radians());
+        let offset = Vector3::new(3.0, 1.0, -2.0);
+        let center = offset + rotation * Vector3::new(0.5, 0.0, 0.0);
+
+        let ray = Ray::new(
+            center + Vector3::new(0.3, 0.2, 5.0),
+            Vector3::new(0.0, 0.0, -1.0),
+        );
+        let mut hit = HitRecord::new();
+        assert!(moved_sphere().hit(&ray, 0.0001, f64::MAX, &mut hit));
+
+        assert_close(hit.object_point, rotation.inverse() * (hit.point - offset));
+        assert_close(
+            hit.object_rotation * (hit.point - center),
+            hit.object_point - Vector3::new(0.5, 0.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn object_space_textures_move_with_the_object() {
+        let white = SolidColorTexture::arc(Vector3::repeat(1.0));
+        let black = SolidColorTexture::arc(Vector3::zeros());
+        let object = CheckerTexture::arc_space(white.clone(), black.clone(), TextureSpace::Object);
+        let world = CheckerTexture::arc_space(white, black, TextureSpace::World);
+
+        let untransformed = Sphere::arc(
+            Vector3::new(0.5, 0.0, 0.0),
+            1.0,
+            EmissiveMaterial::arc(SolidColorTexture::arc(Vector3::repeat(1.0))),
+        );
+
+        let rotation = Rotation3::from_axis_angle(&Vector3::y_axis(), 60f64.to_radians());
+        let center = Vector3::new(3.0, 1.0, -2.0) + rotation * Vector3::new(0.5, 0.0, 0.0);
+
+        let (mut moved, mut still) = (0, 0);
+        for i in 0..100 {
+            // Directions spread around the sphere, the same in object space for both spheres.
+            let angle = 2.4 * i as f64;
+            let direction = -Vector3::new(angle.cos(), 0.8 * angle.sin(), 0.6).normalize();
+
+            let mut hit = HitRecord::new();
+            let ray = Ray::new(center - 5.0 * (rotation * direction), rotation * direction);
+            assert!(moved_sphere().hit(&ray, 0.0001, f64::MAX, &mut hit));
+
+            let mut reference = HitRecord::new();
+            let ray = Ray::new(Vector3::new(0.5, 0.0, 0.0) - 5.0 * direction, direction);
+            assert!(untransformed.hit(&ray, 0.0001, f64::MAX, &mut reference));
+
+            let color = |texture: &Arc<CheckerTexture>, hit: &HitRecord| {
+                texture.get_color(hit.uv, &hit.texture_point())
+            };
+            if color(&object, &hit) == color(&object, &reference) {
+                moved += 1;
+            }
+            if color(&world, &hit) == color(&world, &reference) {
+                still += 1;
+            }
+        }
+
+        assert_eq!(moved, 100);
+        assert!(still < 100);
+    }
+}
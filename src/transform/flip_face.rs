@@ -0,0 +1,34 @@
+use crate::aabb::AABB;
+use crate::hittable::HitRecord;
+use crate::hittable::Hittable;
+use crate::ray::Ray;
+
+use std::sync::Arc;
+
+// Swaps the outside and inside of a surface, such as a rectangle whose normal points into a box.
+pub struct FlipFace {
+    hittable: Arc<dyn Hittable>,
+}
+
+impl FlipFace {
+    pub fn arc(hittable: Arc<dyn Hittable>) -> Arc<FlipFace> {
+        Arc::new(FlipFace { hittable })
+    }
+}
+
+impl Hittable for FlipFace {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, hit: &mut HitRecord) -> bool {
+        if !self.hittable.hit(ray, t_min, t_max, hit) {
+            return false;
+        }
+
+        // The normals keep facing the ray, only the side it came from changes.
+        hit.front_face = !hit.front_face;
+
+        true
+    }
+
+    fn bounding_box(&self, output_box: &mut AABB) -> bool {
+        self.hittable.bounding_box(output_box)
+    }
+}
@@ -6,7 +6,7 @@ use crate::vector3_traits::Helpers;
 
 use std::sync::Arc;
 
-use nalgebra::Vector3;
+use nalgebra::{Rotation3, Vector3};
 
 pub struct RotateY {
     sin_theta: f64,
@@ -63,16 +63,16 @@ impl RotateY {
 
 impl Hittable for RotateY {
     fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, hit: &mut HitRecord) -> bool {
-        let mut origin = ray.origin;
-        let mut direction = ray.direction;
+        let mut ray_rotated = *ray;
+        ray_rotated.world.get_or_insert((ray.origin, ray.direction));
 
-        origin[0] = self.cos_theta * ray.origin.x - self.sin_theta * ray.origin.z;
-        origin[2] = self.sin_theta * ray.origin.x + self.cos_theta * ray.origin.z;
+        ray_rotated.origin[0] = self.cos_theta * ray.origin.x - self.sin_theta * ray.origin.z;
+        ray_rotated.origin[2] = self.sin_theta * ray.origin.x + self.cos_theta * ray.origin.z;
 
-        direction[0] = self.cos_theta * ray.direction.x - self.sin_theta * ray.direction.z;
-        direction[2] = self.sin_theta * ray.direction.x + self.cos_theta * ray.direction.z;
-
-        let ray_rotated = Ray::new(origin, direction);
+        ray_rotated.direction[0] =
+            self.cos_theta * ray.direction.x - self.sin_theta * ray.direction.z;
+        ray_rotated.direction[2] =
+            self.sin_theta * ray.direction.x + self.cos_theta * ray.direction.z;
         if self.hittable.hit(&ray_rotated, t_min, t_max, hit) == false {
             return false;
         }
@@ -85,9 +85,30 @@ impl Hittable for RotateY {
         normal[0] = self.cos_theta * hit.normal.x + self.sin_theta * hit.normal.z;
         normal[2] = -self.sin_theta * hit.normal.x + self.cos_theta * hit.normal.z;
 
+        let mut geometric_normal = hit.geometric_normal;
+        geometric_normal[0] =
+            self.cos_theta * hit.geometric_normal.x + self.sin_theta * hit.geometric_normal.z;
+        geometric_normal[2] =
+            -self.sin_theta * hit.geometric_normal.x + self.cos_theta * hit.geometric_normal.z;
+
+        let mut tangent = hit.tangent;
+        tangent[0] = self.cos_theta * hit.tangent.x + self.sin_theta * hit.tangent.z;
+        tangent[2] = -self.sin_theta * hit.tangent.x + self.cos_theta * hit.tangent.z;
+
+        let mut bitangent = hit.bitangent;
+        bitangent[0] = self.cos_theta * hit.bitangent.x + self.sin_theta * hit.bitangent.z;
+        bitangent[2] = -self.sin_theta * hit.bitangent.x + self.cos_theta * hit.bitangent.z;
+
+        // The shape already turned the normals against the ray and recorded which side was hit,
+        // which the rotation preserves.
         hit.point = point;
         hit.normal = normal;
-        hit.set_face_normal(ray_rotated.direction, hit.normal);
+        hit.geometric_normal = geometric_normal;
+        // World space directions are turned the same way as the ray before reaching the shape.
+        hit.object_rotation = hit.object_rotation
+            * Rotation3::from_axis_angle(&Vector3::y_axis(), -self.sin_theta.atan2(self.cos_theta));
+        hit.tangent = tangent;
+        hit.bitangent = bitangent;
 
         true
     }
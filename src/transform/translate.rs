@@ -19,14 +19,17 @@ impl Translate {
 }
 impl Hittable for Translate {
     fn hit(&self, ray: &Ray, t_min: f64, t_max: f64, hit: &mut HitRecord) -> bool {
-        let ray_offset = Ray::new(ray.origin - self.offset, ray.direction);
+        let mut ray_offset = *ray;
+        ray_offset.world.get_or_insert((ray.origin, ray.direction));
+        ray_offset.origin -= self.offset;
 
         if self.hittable.hit(&ray_offset, t_min, t_max, hit) == false {
             return false;
         }
 
+        // The normals were already turned against the ray by the shape, which also knows which
+        // side was hit, and a translation leaves them unchanged.
         hit.point += self.offset;
-        hit.set_face_normal(ray_offset.direction, hit.normal);
 
         true
     }
@@ -5,6 +5,7 @@ pub trait Helpers {
     fn infinity() -> Vector3<f64>;
 
     fn gamma(self, gamma: f64) -> Vector3<f64>;
+    fn luminance(&self) -> f64;
     fn reflection(v: Vector3<f64>, n: Vector3<f64>) -> Vector3<f64>;
     fn refraction(uv: Vector3<f64>, n: Vector3<f64>, etai_over_etat: f64) -> Vector3<f64>;
 
@@ -26,6 +27,11 @@ impl Helpers for Vector3<f64> {
         Vector3::new(gamma_r, gamma_g, gamma_b)
     }
 
+    // Relative luminance of a linear Rec. 709 color.
+    fn luminance(&self) -> f64 {
+        0.2126 * self.x + 0.7152 * self.y + 0.0722 * self.z
+    }
+
     fn reflection(v: Vector3<f64>, n: Vector3<f64>) -> Vector3<f64> {
         v - 2.0 * Vector3::dot(&v, &n) * n
     }